@@ -0,0 +1,208 @@
+use globset::{GlobBuilder, GlobMatcher};
+use log::{debug, warn};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+mod tests;
+
+/// Where GitHub and GitLab look for the CODEOWNERS file, in order of precedence
+const CODEOWNERS_LOCATIONS: [&str; 4] = [
+    ".github/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+];
+
+#[derive(Debug)]
+struct Rule {
+    matchers: Vec<GlobMatcher>,
+    owners: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Section {
+    default_owners: Vec<String>,
+    rules: Vec<Rule>,
+}
+
+/// A parsed CODEOWNERS file. Supports the GitHub syntax and GitLab sections: within a section the
+/// last matching rule wins, and the owners of every section that matches are combined.
+#[derive(Debug, Default)]
+pub struct CodeOwners {
+    sections: Vec<Section>,
+}
+
+/// The CODEOWNERS files of the analyzed repositories, each read and compiled once
+#[derive(Default)]
+pub struct CodeOwnersCache {
+    code_owners: RefCell<HashMap<PathBuf, Option<Rc<CodeOwners>>>>,
+}
+
+impl CodeOwnersCache {
+    pub fn new() -> CodeOwnersCache {
+        CodeOwnersCache::default()
+    }
+
+    /// The CODEOWNERS of the repository at `root`, see `CodeOwners::discover`
+    pub fn for_root(&self, root: &str) -> Option<Rc<CodeOwners>> {
+        let key = fs::canonicalize(root).unwrap_or_else(|_| PathBuf::from(root));
+
+        self.code_owners
+            .borrow_mut()
+            .entry(key)
+            .or_insert_with(|| CodeOwners::discover(root).map(Rc::new))
+            .clone()
+    }
+}
+
+/// Splits a line on whitespace, honouring `\ ` escapes in paths
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// Converts a gitignore-style CODEOWNERS pattern into globs relative to the repository root
+fn pattern_to_globs(pattern: &str) -> Vec<String> {
+    let is_directory = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.starts_with('/') || trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+
+    let base = if trimmed.is_empty() || trimmed == "*" {
+        return vec!["**".to_string()];
+    } else if anchored {
+        trimmed.to_string()
+    } else {
+        format!("**/{}", trimmed)
+    };
+
+    let last_segment = base.rsplit('/').next().unwrap_or_default();
+    if is_directory {
+        vec![format!("{}/**", base)]
+    } else if last_segment.contains(['*', '?', '[']) {
+        // e.g. `docs/*` only matches the files directly inside docs
+        vec![base]
+    } else {
+        // A plain name matches files as well as everything under directories of that name
+        vec![base.clone(), format!("{}/**", base)]
+    }
+}
+
+/// Parses a GitLab section header such as `[Frontend]`, `^[Docs][2] @docs-team`
+fn parse_section_header(line: &str) -> Option<Section> {
+    let header = line.strip_prefix('^').unwrap_or(line);
+    if !header.starts_with('[') {
+        return None;
+    }
+    let end = header.find(']')?;
+    let mut rest = &header[end + 1..];
+
+    // Skip the optional required approvals count, e.g. [2]
+    if rest.starts_with('[') {
+        rest = &rest[rest.find(']')? + 1..];
+    }
+
+    Some(Section {
+        default_owners: tokenize(rest),
+        rules: Vec::new(),
+    })
+}
+
+impl CodeOwners {
+    pub fn parse(content: &str) -> CodeOwners {
+        let mut sections: Vec<Section> = vec![Section::default()];
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(section) = parse_section_header(line) {
+                sections.push(section);
+                continue;
+            }
+
+            let mut tokens = tokenize(line).into_iter();
+            let pattern = match tokens.next() {
+                Some(pattern) => pattern,
+                None => continue,
+            };
+            let owners: Vec<String> = tokens.take_while(|token| !token.starts_with('#')).collect();
+
+            let mut matchers: Vec<GlobMatcher> = Vec::new();
+            for glob in pattern_to_globs(&pattern) {
+                match GlobBuilder::new(&glob).literal_separator(true).build() {
+                    Ok(glob) => matchers.push(glob.compile_matcher()),
+                    Err(err) => warn!("Ignoring invalid CODEOWNERS pattern {}: {}", pattern, err),
+                }
+            }
+
+            let section = sections.last_mut().unwrap();
+            let owners = if owners.is_empty() {
+                section.default_owners.clone()
+            } else {
+                owners
+            };
+            section.rules.push(Rule { matchers, owners });
+        }
+
+        CodeOwners { sections }
+    }
+
+    /// Reads the CODEOWNERS file of the repository at `root`, if there is one
+    pub fn discover(root: &str) -> Option<CodeOwners> {
+        CODEOWNERS_LOCATIONS.iter().find_map(|location| {
+            let path = Path::new(root).join(location);
+            let content = fs::read_to_string(&path).ok()?;
+            debug!("Using code owners from {}", path.display());
+            Some(CodeOwners::parse(&content))
+        })
+    }
+
+    /// The owners of a file, given its path relative to the repository root
+    pub fn owners_for(&self, relative_path: &str) -> Vec<String> {
+        let mut owners: Vec<String> = Vec::new();
+
+        for section in &self.sections {
+            let rule = section.rules.iter().rev().find(|rule| {
+                rule.matchers
+                    .iter()
+                    .any(|matcher| matcher.is_match(relative_path))
+            });
+
+            for owner in rule.map(|rule| &rule.owners).into_iter().flatten() {
+                if !owners.contains(owner) {
+                    owners.push(owner.clone());
+                }
+            }
+        }
+
+        owners
+    }
+}
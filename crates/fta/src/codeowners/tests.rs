@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use crate::codeowners::{CodeOwners, CodeOwnersCache};
+    use std::fs;
+    use std::rc::Rc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let code_owners = CodeOwners::parse(
+            r#"
+            # Default owners
+            *                 @org/everyone
+            *.ts              @org/typescript
+            /src/legacy/      @org/legacy @alice
+            docs/*            docs@example.com
+            apps/web          @org/web
+            "#,
+        );
+
+        assert_eq!(code_owners.owners_for("README.md"), vec!["@org/everyone"]);
+        assert_eq!(
+            code_owners.owners_for("lib/deep/a.ts"),
+            vec!["@org/typescript"]
+        );
+        assert_eq!(
+            code_owners.owners_for("src/legacy/old.ts"),
+            vec!["@org/legacy", "@alice"]
+        );
+        assert_eq!(
+            code_owners.owners_for("docs/intro.ts"),
+            vec!["docs@example.com"]
+        );
+        // `*` doesn't cross directories
+        assert_eq!(
+            code_owners.owners_for("docs/api/intro.ts"),
+            vec!["@org/typescript"]
+        );
+        assert_eq!(
+            code_owners.owners_for("apps/web/src/app.tsx"),
+            vec!["@org/web"]
+        );
+    }
+
+    #[test]
+    fn test_rule_without_owners_unassigns() {
+        let code_owners = CodeOwners::parse(
+            r#"
+            *              @org/everyone
+            /generated/
+            "#,
+        );
+
+        assert!(code_owners.owners_for("generated/api.ts").is_empty());
+    }
+
+    #[test]
+    fn test_escaped_spaces() {
+        let code_owners = CodeOwners::parse(r#"/my\ dir/ @org/spaces"#);
+
+        assert_eq!(code_owners.owners_for("my dir/a.ts"), vec!["@org/spaces"]);
+    }
+
+    #[test]
+    fn test_gitlab_sections_combine_owners() {
+        let code_owners = CodeOwners::parse(
+            r#"
+            * @org/everyone
+
+            [Frontend] @org/frontend
+            /apps/web/
+            /apps/web/payments/ @org/payments
+
+            ^[Security][2] @org/security
+            **/auth/**
+            "#,
+        );
+
+        assert_eq!(
+            code_owners.owners_for("apps/web/index.ts"),
+            vec!["@org/everyone", "@org/frontend"]
+        );
+        assert_eq!(
+            code_owners.owners_for("apps/web/payments/auth/login.ts"),
+            vec!["@org/everyone", "@org/payments", "@org/security"]
+        );
+        assert_eq!(code_owners.owners_for("lib/a.ts"), vec!["@org/everyone"]);
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        assert!(CodeOwners::discover(root).is_none());
+
+        fs::create_dir(dir.path().join(".github")).unwrap();
+        fs::write(dir.path().join(".github/CODEOWNERS"), "* @org/github").unwrap();
+        fs::write(dir.path().join("CODEOWNERS"), "* @org/root").unwrap();
+
+        let code_owners = CodeOwners::discover(root).unwrap();
+        assert_eq!(code_owners.owners_for("a.ts"), vec!["@org/github"]);
+    }
+
+    #[test]
+    fn test_cache_compiles_each_repository_once() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("a")).unwrap();
+        fs::create_dir_all(dir.path().join("b")).unwrap();
+        fs::write(dir.path().join("a/CODEOWNERS"), "* @org/a").unwrap();
+        let a = dir.path().join("a").display().to_string();
+        let b = dir.path().join("b").display().to_string();
+
+        let cache = CodeOwnersCache::new();
+        let code_owners = cache.for_root(&a).unwrap();
+        assert_eq!(code_owners.owners_for("x.ts"), vec!["@org/a"]);
+        assert!(cache.for_root(&b).is_none());
+
+        // Later lookups of the same repository reuse the compiled file
+        fs::remove_file(dir.path().join("a/CODEOWNERS")).unwrap();
+        let again = cache.for_root(&format!("{}/../a", b)).unwrap();
+        assert!(Rc::ptr_eq(&code_owners, &again));
+    }
+}
@@ -1,8 +1,16 @@
-use crate::structs::{FtaConfigOptional, FtaConfigResolved};
+use crate::parse::es_version;
+use crate::structs::ConfigMatchers;
+pub use crate::structs::{
+    Encoding, FtaConfigOptional, FtaConfigOverride, FtaConfigResolved, ParserOptions, ScoreLines,
+};
+use crate::utils::{exclude_directories_matcher, exclude_filenames_matcher};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::gitignore::GitignoreBuilder;
+use log::{debug, warn};
+use serde_json::Value;
 use std::fmt;
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 mod tests;
 
@@ -35,20 +43,83 @@ impl From<FtaConfigOptional> for FtaConfigResolved {
             include_comments: opt_config
                 .include_comments
                 .unwrap_or(default_config.include_comments),
+            score_lines: opt_config.score_lines.unwrap_or(default_config.score_lines),
             exclude_under: opt_config
                 .exclude_under
                 .unwrap_or(default_config.exclude_under),
+            max_file_size: opt_config
+                .max_file_size
+                .unwrap_or(default_config.max_file_size),
+            encoding: opt_config.encoding.unwrap_or(default_config.encoding),
+            include_generated: opt_config
+                .include_generated
+                .unwrap_or(default_config.include_generated),
+            file_timeout_ms: opt_config
+                .file_timeout_ms
+                .or(default_config.file_timeout_ms),
+            overrides: opt_config.overrides.unwrap_or(default_config.overrides),
+            parser: opt_config.parser.unwrap_or(default_config.parser),
+            command_line: FtaConfigOptional::default(),
+            matchers: Default::default(),
         }
     }
 }
 
+/// Every option fta.json accepts. Overrides accept the same options, except `overrides` and
+/// `extends`.
+const CONFIG_OPTIONS: [&str; 16] = [
+    "extends",
+    "replace",
+    "extensions",
+    "exclude_filenames",
+    "exclude_directories",
+    "output_limit",
+    "score_cap",
+    "include_comments",
+    "score_lines",
+    "exclude_under",
+    "max_file_size",
+    "encoding",
+    "include_generated",
+    "file_timeout_ms",
+    "overrides",
+    "parser",
+];
+
+/// Options whose values are added to the lists of the config below, rather than replacing them
+const LIST_OPTIONS: [&str; 4] = [
+    "extensions",
+    "exclude_filenames",
+    "exclude_directories",
+    "overrides",
+];
+
+/// The config files looked up in each directory, in order of precedence. Starting from the analyzed
+/// project, the first directory with any of them provides the config; `package.json` only counts
+/// when it has an `"fta"` key. Other config files in that same directory are ignored.
+pub const CONFIG_FILE_NAMES: [&str; 6] = [
+    "fta.json",
+    ".ftarc.json",
+    "fta.yaml",
+    "fta.yml",
+    "fta.toml",
+    "package.json",
+];
+
+/// Selectors that pick the files an override applies to
+const OVERRIDE_SELECTORS: [&str; 2] = ["files", "owners"];
+
 pub fn get_default_config() -> FtaConfigResolved {
-    let default_config = FtaConfigResolved {
+    FtaConfigResolved {
         extensions: vec![
             ".js".to_string(),
             ".jsx".to_string(),
             ".ts".to_string(),
             ".tsx".to_string(),
+            ".mjs".to_string(),
+            ".cjs".to_string(),
+            ".mts".to_string(),
+            ".cts".to_string(),
         ],
         exclude_filenames: vec![
             ".d.ts".to_string(),
@@ -63,67 +134,1040 @@ pub fn get_default_config() -> FtaConfigResolved {
         output_limit: 5000,
         score_cap: 1000,
         include_comments: false,
+        score_lines: ScoreLines::Source,
         exclude_under: 6,
+        max_file_size: 1024 * 1024,
+        encoding: Encoding::Utf8,
+        include_generated: false,
+        file_timeout_ms: None,
+        overrides: vec![],
+        parser: ParserOptions::default(),
+        command_line: FtaConfigOptional::default(),
+        matchers: Default::default(),
+    }
+}
+
+/// Layers a partial config on top of `base`. For extensions, filenames, exclude_directories and
+/// overrides, provided values are added to the base lists unless the option is listed in
+/// `replace`, moving values the base already has to the end; parser options are set one by one;
+/// other values replace the base ones.
+pub fn merge_config(
+    base: &FtaConfigResolved,
+    provided_config: FtaConfigOptional,
+) -> FtaConfigResolved {
+    let base = base.clone();
+    let replace = provided_config.replace.unwrap_or_default();
+    let list = |option: &str, mut base: Vec<_>, provided: Option<Vec<_>>| match provided {
+        Some(provided) if replace.iter().any(|replaced| replaced == option) => provided,
+        Some(mut provided) => {
+            base.retain(|value| !provided.contains(value));
+            base.append(&mut provided);
+            base
+        }
+        None => base,
     };
 
-    default_config
+    FtaConfigResolved {
+        extensions: list("extensions", base.extensions, provided_config.extensions),
+        exclude_filenames: list(
+            "exclude_filenames",
+            base.exclude_filenames,
+            provided_config.exclude_filenames,
+        ),
+        exclude_directories: list(
+            "exclude_directories",
+            base.exclude_directories,
+            provided_config.exclude_directories,
+        ),
+        output_limit: provided_config.output_limit.unwrap_or(base.output_limit),
+        score_cap: provided_config.score_cap.unwrap_or(base.score_cap),
+        exclude_under: provided_config.exclude_under.unwrap_or(base.exclude_under),
+        max_file_size: provided_config.max_file_size.unwrap_or(base.max_file_size),
+        encoding: provided_config.encoding.unwrap_or(base.encoding),
+        include_generated: provided_config
+            .include_generated
+            .unwrap_or(base.include_generated),
+        file_timeout_ms: provided_config.file_timeout_ms.or(base.file_timeout_ms),
+        include_comments: provided_config
+            .include_comments
+            .unwrap_or(base.include_comments),
+        score_lines: provided_config.score_lines.unwrap_or(base.score_lines),
+        overrides: {
+            let mut overrides = base.overrides;
+            if let Some(mut provided) = provided_config.overrides {
+                if replace.iter().any(|replaced| replaced == "overrides") {
+                    overrides.clear();
+                }
+                overrides.retain(|config_override| !provided.contains(config_override));
+                overrides.append(&mut provided);
+            }
+            overrides
+        },
+        parser: match provided_config.parser {
+            Some(parser) => parser.or(base.parser),
+            None => base.parser,
+        },
+        command_line: base.command_line,
+        matchers: Default::default(),
+    }
+}
+
+/// Turns an override's `files` pattern into a glob matching file names as they are reported.
+/// Like in .gitignore, a pattern without a slash matches in any directory.
+fn override_glob(pattern: &str) -> String {
+    let pattern = pattern.trim_start_matches("./");
+    if pattern.contains('/') {
+        pattern.trim_start_matches('/').to_string()
+    } else {
+        format!("**/{}", pattern)
+    }
+}
+
+/// Compiles the `files` globs of an override. Invalid globs match nothing.
+fn files_matcher(patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        if let Ok(glob) = GlobBuilder::new(&override_glob(pattern))
+            .literal_separator(true)
+            .build()
+        {
+            builder.add(glob);
+        }
+    }
+
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+impl FtaConfigResolved {
+    /// The patterns of the config compiled for matching. They're compiled once per config, the
+    /// first time a file is matched against them.
+    pub(crate) fn matchers(&self) -> &ConfigMatchers {
+        self.matchers.get_or_init(|| ConfigMatchers {
+            exclude_filenames: exclude_filenames_matcher(&self.exclude_filenames),
+            exclude_directories: exclude_directories_matcher(&self.exclude_directories),
+            override_files: self
+                .overrides
+                .iter()
+                .map(|config_override| config_override.files.as_deref().map(files_matcher))
+                .collect(),
+        })
+    }
+}
+
+/// Layers the overrides matching a file on top of `config`, in the order they are declared. An
+/// override matches when all of its selectors do: `files` globs against the file name as reported,
+/// `owners` against any of the file's CODEOWNERS owners. Returns `None` when no override applies.
+pub fn apply_overrides(
+    config: &FtaConfigResolved,
+    file_name: &str,
+    owners: &[String],
+) -> Option<FtaConfigResolved> {
+    let file_name = file_name.trim_start_matches('/');
+    let matching: Vec<&FtaConfigOverride> = config
+        .overrides
+        .iter()
+        .zip(&config.matchers().override_files)
+        .filter(|(config_override, files)| {
+            let override_owners = config_override.owners.as_ref();
+
+            (files.is_some() || override_owners.is_some())
+                && files.as_ref().is_none_or(|files| files.is_match(file_name))
+                && override_owners.is_none_or(|override_owners| {
+                    owners.iter().any(|owner| override_owners.contains(owner))
+                })
+        })
+        .map(|(config_override, _)| config_override)
+        .collect();
+
+    if matching.is_empty() {
+        return None;
+    }
+
+    let resolved = matching
+        .iter()
+        .fold(config.clone(), |resolved, config_override| {
+            merge_config(&resolved, config_override.config.clone())
+        });
+    Some(merge_config(&resolved, resolved.command_line.clone()))
+}
+
+/// Rebases the `files` globs of a nested config's overrides onto `dir`, the directory of that
+/// config relative to where file names are reported from.
+pub fn scope_overrides(mut provided_config: FtaConfigOptional, dir: &str) -> FtaConfigOptional {
+    for config_override in provided_config.overrides.iter_mut().flatten() {
+        for pattern in config_override.files.iter_mut().flatten() {
+            *pattern = format!("{}/{}", dir, override_glob(pattern));
+        }
+    }
+
+    provided_config
+}
+
+/// Rebases the anchored `exclude_directories` patterns of a nested config onto `dir`, the directory
+/// of that config relative to the analyzed root, e.g. `/generated` or `!/src/bin`. Patterns without
+/// a slash already match in any directory of the subtree the config applies to.
+pub fn scope_exclude_directories(
+    mut provided_config: FtaConfigOptional,
+    dir: &str,
+) -> FtaConfigOptional {
+    for pattern in provided_config.exclude_directories.iter_mut().flatten() {
+        let (negation, path) = match pattern.strip_prefix('!') {
+            Some(path) => ("!", path),
+            None => ("", pattern.as_str()),
+        };
+        if path.trim_end_matches('/').contains('/') {
+            *pattern = format!("{}/{}/{}", negation, dir, path.trim_start_matches('/'));
+        }
+    }
+
+    provided_config
+}
+
+/// Layers the config file of a nested directory on top of `base`, the config of its parent. Its
+/// patterns are rebased onto the directory, which is `name_prefix` relative to where file names are
+/// reported from and `dir` relative to the analyzed root. Settings from the command line still take
+/// precedence over it.
+pub fn merge_nested_config(
+    base: &FtaConfigResolved,
+    provided_config: FtaConfigOptional,
+    name_prefix: &str,
+    dir: &str,
+) -> FtaConfigResolved {
+    let provided_config =
+        scope_exclude_directories(scope_overrides(provided_config, name_prefix), dir);
+    let config = merge_config(base, provided_config);
+    merge_config(&config, config.command_line.clone())
+}
+
+/// Resolves the config of a project from its config file `provided_config` on top of the defaults,
+/// for a config file found elsewhere than the one `base` was resolved from, e.g. in a git tree.
+/// Settings from the command line that `base` was resolved with still take precedence over it.
+pub fn merge_root_config(
+    base: &FtaConfigResolved,
+    provided_config: FtaConfigOptional,
+) -> FtaConfigResolved {
+    let mut defaults = get_default_config();
+    defaults.command_line = base.command_line.clone();
+    let config = merge_config(&defaults, provided_config);
+    merge_config(&config, config.command_line.clone())
 }
 
 pub fn read_config(
     config_path: String,
     path_specified_by_user: bool,
 ) -> Result<FtaConfigResolved, ConfigError> {
-    let default_config = get_default_config();
+    read_config_onto(config_path, path_specified_by_user, &get_default_config())
+}
+
+/// Like `read_config`, but layers the file on top of `base` rather than the defaults.
+pub fn read_config_onto(
+    config_path: String,
+    path_specified_by_user: bool,
+    base: &FtaConfigResolved,
+) -> Result<FtaConfigResolved, ConfigError> {
     if Path::new(&config_path).exists() {
-        let mut file = File::open(config_path).unwrap();
-        let mut content = String::new();
-        file.read_to_string(&mut content).unwrap();
-        let provided_config: FtaConfigOptional = serde_json::from_str(&content).unwrap_or_default();
-
-        // For extensions, filenames and exclude_directories,
-        // user-provided values are added to the defaults.
-        return Result::Ok(FtaConfigResolved {
-            extensions: {
-                let mut extensions = default_config.extensions;
-                if let Some(mut provided) = provided_config.extensions {
-                    extensions.append(&mut provided);
-                }
-                extensions
-            },
-            exclude_filenames: {
-                let mut exclude_filenames = default_config.exclude_filenames;
-                if let Some(mut provided) = provided_config.exclude_filenames {
-                    exclude_filenames.append(&mut provided);
-                }
-                exclude_filenames
-            },
-            exclude_directories: {
-                let mut exclude_directories = default_config.exclude_directories;
-                if let Some(mut provided) = provided_config.exclude_directories {
-                    exclude_directories.append(&mut provided);
-                }
-                exclude_directories
-            },
-            output_limit: provided_config
-                .output_limit
-                .unwrap_or(default_config.output_limit),
-            score_cap: provided_config
-                .score_cap
-                .unwrap_or(default_config.score_cap),
-            exclude_under: provided_config
-                .exclude_under
-                .unwrap_or(default_config.exclude_under),
-            include_comments: provided_config
-                .include_comments
-                .unwrap_or(default_config.include_comments),
-        });
+        let provided_config = load_config(&config_path)?;
+
+        return Result::Ok(merge_config(base, provided_config));
     }
 
     if !path_specified_by_user {
-        return Result::Ok(default_config);
+        return Result::Ok(base.clone());
     }
 
     Result::Err(ConfigError {
         message: format!("Config file not found at file path: {}", config_path),
     })
 }
+
+/// Where each option of a resolved config came from: "default", a config file, a CLI flag...
+/// List options name every source that contributed to them.
+#[derive(Debug, Clone)]
+pub struct ConfigSources {
+    sources: Vec<(&'static str, Vec<String>)>,
+}
+
+impl Default for ConfigSources {
+    fn default() -> Self {
+        ConfigSources {
+            sources: CONFIG_OPTIONS
+                .iter()
+                .map(|option| (*option, vec!["default".to_string()]))
+                .collect(),
+        }
+    }
+}
+
+impl ConfigSources {
+    /// Records `source` for every option set in `provided`
+    pub fn record(&mut self, provided: &FtaConfigOptional, source: &str) {
+        let provided_options = [
+            ("extensions", provided.extensions.is_some()),
+            ("exclude_filenames", provided.exclude_filenames.is_some()),
+            (
+                "exclude_directories",
+                provided.exclude_directories.is_some(),
+            ),
+            ("output_limit", provided.output_limit.is_some()),
+            ("score_cap", provided.score_cap.is_some()),
+            ("include_comments", provided.include_comments.is_some()),
+            ("score_lines", provided.score_lines.is_some()),
+            ("exclude_under", provided.exclude_under.is_some()),
+            ("max_file_size", provided.max_file_size.is_some()),
+            ("encoding", provided.encoding.is_some()),
+            ("include_generated", provided.include_generated.is_some()),
+            ("file_timeout_ms", provided.file_timeout_ms.is_some()),
+            ("overrides", provided.overrides.is_some()),
+            ("parser", provided.parser.is_some()),
+        ];
+
+        let replace = provided.replace.as_deref().unwrap_or_default();
+        for (option, is_provided) in provided_options {
+            if !is_provided {
+                continue;
+            }
+            if replace.iter().any(|replaced| replaced == option) {
+                self.clear(option);
+            }
+            self.set(option, source);
+        }
+    }
+
+    /// Records that `source` set `option`, replacing the previous sources unless it's a list
+    pub fn set(&mut self, option: &str, source: &str) {
+        if let Some((_, sources)) = self.sources.iter_mut().find(|(name, _)| *name == option) {
+            if !LIST_OPTIONS.contains(&option) {
+                sources.clear();
+            }
+            sources.push(source.to_string());
+        }
+    }
+
+    fn clear(&mut self, option: &str) {
+        if let Some((_, sources)) = self.sources.iter_mut().find(|(name, _)| *name == option) {
+            sources.clear();
+        }
+    }
+
+    pub fn get(&self, option: &str) -> &[String] {
+        self.sources
+            .iter()
+            .find(|(name, _)| *name == option)
+            .map(|(_, sources)| sources.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+fn has_fta_key(package_json: &Path) -> bool {
+    fs::read_to_string(package_json).is_ok_and(|content| is_fta_package_json(&content))
+}
+
+/// Whether the content of a package.json configures fta, see `CONFIG_FILE_NAMES`
+pub fn is_fta_package_json(content: &str) -> bool {
+    serde_json::from_str::<Value>(content).is_ok_and(|value| value.get("fta").is_some())
+}
+
+/// Finds the config file of a single directory, see `CONFIG_FILE_NAMES`
+pub fn find_config_in(dir: &Path) -> Option<PathBuf> {
+    let mut candidates = CONFIG_FILE_NAMES
+        .iter()
+        .map(|file_name| dir.join(file_name))
+        .filter(
+            |path| match path.file_name().and_then(|name| name.to_str()) {
+                Some("package.json") => has_fta_key(path),
+                _ => path.is_file(),
+            },
+        );
+
+    let config_path = candidates.next()?;
+    for ignored in candidates {
+        warn!(
+            "Ignoring {}, {} takes precedence",
+            ignored.display(),
+            config_path.display()
+        );
+    }
+
+    Some(config_path)
+}
+
+/// Finds the config file for a project in `start_dir`, walking up its parent directories
+pub fn find_config(start_dir: &Path) -> Option<PathBuf> {
+    let start_dir = if start_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        start_dir
+    };
+    let start_dir = fs::canonicalize(start_dir).unwrap_or_else(|_| start_dir.to_path_buf());
+
+    start_dir.ancestors().find_map(find_config_in)
+}
+
+/// Resolves the config of a project: the file at `config_path` when given, otherwise the one
+/// discovered from `start_dir`, layered on top of the defaults.
+pub fn discover_config(
+    start_dir: &Path,
+    config_path: Option<String>,
+) -> Result<(FtaConfigResolved, ConfigSources), ConfigError> {
+    let mut sources = ConfigSources::default();
+    let config_path = match config_path {
+        Some(config_path) if !Path::new(&config_path).exists() => {
+            return Err(ConfigError {
+                message: format!("Config file not found at file path: {}", config_path),
+            });
+        }
+        Some(config_path) => Some(config_path),
+        None => find_config(start_dir).map(|path| path.display().to_string()),
+    };
+
+    match config_path {
+        Some(config_path) => {
+            debug!("Using config file {}", config_path);
+            let mut config = get_default_config();
+            for (layer_path, provided_config) in load_config_layers(&config_path)? {
+                sources.record(&provided_config, &layer_path);
+                config = merge_config(&config, provided_config);
+            }
+            Ok((config, sources))
+        }
+        None => Ok((get_default_config(), sources)),
+    }
+}
+
+/// Prefix of the environment variables that set options, e.g. `FTA_SCORE_CAP=70`
+const ENV_PREFIX: &str = "FTA_";
+
+/// Parses a `--set` setting into a partial config. `option=value` sets the option, replacing the
+/// lists below for list options, while `option+=value` adds to a list option. List values are
+/// comma-separated or a JSON array, and other values are read as JSON, falling back to a string.
+pub fn parse_setting(setting: &str, source: &str) -> Result<FtaConfigOptional, ConfigError> {
+    let (option, value) = setting.split_once('=').ok_or_else(|| ConfigError {
+        message: format!(
+            "Invalid setting \"{}\" from {}, expected option=value or option+=value",
+            setting, source
+        ),
+    })?;
+    let (option, append) = match option.strip_suffix('+') {
+        Some(option) => (option.trim(), true),
+        None => (option.trim(), false),
+    };
+
+    let is_list = LIST_OPTIONS.contains(&option) || option == "extends" || option == "replace";
+    if append && !is_list {
+        return Err(ConfigError {
+            message: format!(
+                "Invalid setting \"{}\" from {}: only list options can be added to with +=",
+                setting, source
+            ),
+        });
+    }
+
+    let value = if option == "overrides" {
+        match serde_json::from_str(value) {
+            Ok(Value::Object(config_override)) => {
+                Value::Array(vec![Value::Object(config_override)])
+            }
+            Ok(value) => value,
+            Err(err) => {
+                return Err(ConfigError {
+                    message: format!("Invalid JSON for overrides from {}: {}", source, err),
+                })
+            }
+        }
+    } else if is_list && value.trim_start().starts_with('[') {
+        serde_json::from_str(value).map_err(|err| ConfigError {
+            message: format!("Invalid JSON for {} from {}: {}", option, source, err),
+        })?
+    } else if is_list {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| Value::String(item.to_string()))
+            .collect()
+    } else {
+        serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+    };
+
+    let mut object = serde_json::Map::new();
+    object.insert(option.to_string(), value);
+    if !append && LIST_OPTIONS.contains(&option) {
+        object.insert("replace".to_string(), Value::from(vec![option]));
+    }
+
+    config_from_value(Value::Object(object), "", source)
+}
+
+/// The settings given by `FTA_*` environment variables, as `--set` style settings along with the
+/// variable they came from. `FTA_EXTENSIONS=.mts` sets a list, `FTA_EXTENSIONS=+.mts` adds to it.
+pub fn env_settings<I>(vars: I) -> Vec<(String, String)>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let mut settings: Vec<(String, String)> = vars
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let option = name.strip_prefix(ENV_PREFIX)?.to_lowercase();
+            if !CONFIG_OPTIONS.contains(&option.as_str()) {
+                let suggestion = closest_match(&option, &CONFIG_OPTIONS)
+                    .map(|known| format!(", did you mean {}{}?", ENV_PREFIX, known.to_uppercase()))
+                    .unwrap_or_default();
+                warn!(
+                    "Ignoring unknown environment variable {}{}",
+                    name, suggestion
+                );
+                return None;
+            }
+
+            let setting = match value.strip_prefix('+') {
+                Some(value) if LIST_OPTIONS.contains(&option.as_str()) => {
+                    format!("{}+={}", option, value)
+                }
+                _ => format!("{}={}", option, value),
+            };
+            Some((setting, name))
+        })
+        .collect();
+
+    // The environment is unordered, keep the result stable
+    settings.sort();
+    settings
+}
+
+/// Layers a partial config from a setting onto `config` and records its source. Presets it
+/// `extends` are resolved from the current directory and layered first. The setting is also kept
+/// in `command_line`.
+pub fn apply_setting(
+    config: &FtaConfigResolved,
+    sources: &mut ConfigSources,
+    mut provided_config: FtaConfigOptional,
+    source: &str,
+) -> Result<FtaConfigResolved, ConfigError> {
+    let mut config = config.clone();
+
+    for preset in provided_config.extends.take().unwrap_or_default() {
+        let preset_path = resolve_preset(&preset, Path::new("")).ok_or_else(|| ConfigError {
+            message: format!(
+                "Unable to find the config \"{}\" extended by {}",
+                preset, source
+            ),
+        })?;
+        for (layer_path, layer) in load_config_layers(&preset_path.display().to_string())? {
+            sources.record(&layer, &layer_path);
+            config = layer_command_line(&config, layer);
+        }
+    }
+
+    sources.record(&provided_config, source);
+    Ok(layer_command_line(&config, provided_config))
+}
+
+/// Merges a setting into `config` and keeps it in `command_line`
+fn layer_command_line(config: &FtaConfigResolved, setting: FtaConfigOptional) -> FtaConfigResolved {
+    let mut merged = merge_config(config, setting.clone());
+    merged.command_line = merge_optional(merged.command_line, setting);
+    merged
+}
+
+/// Lists every option of the resolved config along with where its value came from
+pub fn format_config(config: &FtaConfigResolved, sources: &ConfigSources) -> String {
+    let values = serde_json::to_value(config).unwrap_or_default();
+
+    CONFIG_OPTIONS
+        .iter()
+        .filter_map(|option| {
+            Some(format!(
+                "{}: {}\n    from {}",
+                option,
+                values.get(option)?,
+                sources.get(option).join(", ")
+            ))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Reads a config file along with the presets it `extends`, combined into a single partial config
+pub fn load_config(config_path: &str) -> Result<FtaConfigOptional, ConfigError> {
+    Ok(load_config_layers(config_path)?
+        .into_iter()
+        .map(|(_, provided_config)| provided_config)
+        .fold(FtaConfigOptional::default(), merge_optional))
+}
+
+/// Reads a config file and, recursively, the presets it `extends`. Returns every config file
+/// involved with the presets first, in the order they should be layered.
+pub fn load_config_layers(
+    config_path: &str,
+) -> Result<Vec<(String, FtaConfigOptional)>, ConfigError> {
+    let mut layers: Vec<(String, FtaConfigOptional)> = Vec::new();
+    collect_layers(Path::new(config_path), &mut Vec::new(), &mut layers)?;
+
+    Ok(layers)
+}
+
+fn collect_layers(
+    config_path: &Path,
+    chain: &mut Vec<PathBuf>,
+    layers: &mut Vec<(String, FtaConfigOptional)>,
+) -> Result<(), ConfigError> {
+    let canonical_path =
+        fs::canonicalize(config_path).unwrap_or_else(|_| config_path.to_path_buf());
+    if chain.contains(&canonical_path) {
+        let cycle: Vec<String> = chain
+            .iter()
+            .chain([&canonical_path])
+            .map(|path| path.display().to_string())
+            .collect();
+        return Err(ConfigError {
+            message: format!("Circular extends: {}", cycle.join(" -> ")),
+        });
+    }
+
+    let config_path_str = config_path.display().to_string();
+    let provided_config = read_config_file(&config_path_str)?;
+    let config_dir = config_path.parent().unwrap_or(Path::new(""));
+
+    chain.push(canonical_path);
+    collect_presets(provided_config, &config_path_str, config_dir, chain, layers)?;
+    chain.pop();
+
+    Ok(())
+}
+
+/// Collects the presets a config `extends`, resolved from `config_dir`, followed by the config
+fn collect_presets(
+    mut provided_config: FtaConfigOptional,
+    config_path: &str,
+    config_dir: &Path,
+    chain: &mut Vec<PathBuf>,
+    layers: &mut Vec<(String, FtaConfigOptional)>,
+) -> Result<(), ConfigError> {
+    for preset in provided_config.extends.take().unwrap_or_default() {
+        let preset_path = resolve_preset(&preset, config_dir).ok_or_else(|| ConfigError {
+            message: format!(
+                "Unable to find the config \"{}\" extended by {}",
+                preset, config_path
+            ),
+        })?;
+        collect_layers(&preset_path, chain, layers)?;
+    }
+
+    layers.push((config_path.to_string(), provided_config));
+    Ok(())
+}
+
+/// Like `load_config`, for the content of a config file that isn't on disk, e.g. a blob of a git
+/// tree. `config_path` names it in errors and picks its format; the presets it `extends` are
+/// resolved from `preset_dir`.
+pub fn load_config_content(
+    config_path: &str,
+    content: &str,
+    preset_dir: &Path,
+) -> Result<FtaConfigOptional, ConfigError> {
+    let mut layers: Vec<(String, FtaConfigOptional)> = Vec::new();
+    let provided_config = parse_config_file(config_path, content)?;
+    collect_presets(
+        provided_config,
+        config_path,
+        preset_dir,
+        &mut Vec::new(),
+        &mut layers,
+    )?;
+
+    Ok(layers
+        .into_iter()
+        .map(|(_, provided_config)| provided_config)
+        .fold(FtaConfigOptional::default(), merge_optional))
+}
+
+/// Finds the config file of an `extends` entry: a path relative to the extending config, or a
+/// package (or a file inside one) under `node_modules` in the config's directory or its parents.
+fn resolve_preset(preset: &str, config_dir: &Path) -> Option<PathBuf> {
+    let resolve = |path: PathBuf| -> Option<PathBuf> {
+        if path.is_file() {
+            Some(path)
+        } else if path.is_dir() {
+            find_config_in(&path)
+        } else {
+            None
+        }
+    };
+
+    if preset.starts_with("./") || preset.starts_with("../") || Path::new(preset).is_absolute() {
+        return resolve(config_dir.join(preset));
+    }
+
+    let config_dir = if config_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        config_dir
+    };
+    let config_dir = fs::canonicalize(config_dir).unwrap_or_else(|_| config_dir.to_path_buf());
+
+    config_dir
+        .ancestors()
+        .map(|dir| dir.join("node_modules").join(preset))
+        .find_map(resolve)
+}
+
+/// Layers one partial config on top of another, with the same semantics as `merge_config`
+fn merge_optional(lower: FtaConfigOptional, upper: FtaConfigOptional) -> FtaConfigOptional {
+    fn append<T>(lower: Option<Vec<T>>, upper: Option<Vec<T>>) -> Option<Vec<T>> {
+        match (lower, upper) {
+            (Some(mut lower), Some(mut upper)) => {
+                lower.append(&mut upper);
+                Some(lower)
+            }
+            (lower, upper) => upper.or(lower),
+        }
+    }
+
+    // A list the upper config replaces discards the lower config's values, and still replaces the
+    // lists the combined config is eventually layered onto
+    let replace = upper.replace.unwrap_or_default();
+    let replaces = |option: &str| replace.iter().any(|replaced| replaced == option);
+    let mut combined_replace = lower.replace.unwrap_or_default();
+    for option in &replace {
+        if !combined_replace.contains(option) {
+            combined_replace.push(option.clone());
+        }
+    }
+
+    let list = |option: &str, lower: Option<Vec<String>>, upper: Option<Vec<String>>| {
+        if replaces(option) && upper.is_some() {
+            upper
+        } else {
+            append(lower, upper)
+        }
+    };
+
+    FtaConfigOptional {
+        extends: None,
+        replace: if combined_replace.is_empty() {
+            None
+        } else {
+            Some(combined_replace)
+        },
+        extensions: list("extensions", lower.extensions, upper.extensions),
+        exclude_filenames: list(
+            "exclude_filenames",
+            lower.exclude_filenames,
+            upper.exclude_filenames,
+        ),
+        exclude_directories: list(
+            "exclude_directories",
+            lower.exclude_directories,
+            upper.exclude_directories,
+        ),
+        output_limit: upper.output_limit.or(lower.output_limit),
+        score_cap: upper.score_cap.or(lower.score_cap),
+        include_comments: upper.include_comments.or(lower.include_comments),
+        score_lines: upper.score_lines.or(lower.score_lines),
+        exclude_under: upper.exclude_under.or(lower.exclude_under),
+        max_file_size: upper.max_file_size.or(lower.max_file_size),
+        encoding: upper.encoding.or(lower.encoding),
+        include_generated: upper.include_generated.or(lower.include_generated),
+        file_timeout_ms: upper.file_timeout_ms.or(lower.file_timeout_ms),
+        overrides: if replaces("overrides") && upper.overrides.is_some() {
+            upper.overrides
+        } else {
+            append(lower.overrides, upper.overrides)
+        },
+        parser: match (lower.parser, upper.parser) {
+            (Some(lower), Some(upper)) => Some(upper.or(lower)),
+            (lower, upper) => upper.or(lower),
+        },
+    }
+}
+
+/// Reads a single config file, picking its format from its name: JSON, YAML, TOML or the `"fta"`
+/// key of a package.json.
+fn read_config_file(config_path: &str) -> Result<FtaConfigOptional, ConfigError> {
+    let content = fs::read_to_string(config_path).map_err(|err| ConfigError {
+        message: format!("Unable to read config file {}: {}", config_path, err),
+    })?;
+
+    parse_config_file(config_path, &content)
+}
+
+/// Parses the content of a config file in the format its name calls for
+fn parse_config_file(config_path: &str, content: &str) -> Result<FtaConfigOptional, ConfigError> {
+    let path = Path::new(config_path);
+    let extension = path.extension().and_then(|ext| ext.to_str());
+
+    if path.file_name().and_then(|name| name.to_str()) == Some("package.json") {
+        let package_json: Value = serde_json::from_str(content).map_err(|err| ConfigError {
+            message: format!("Invalid JSON in {}: {}", config_path, err),
+        })?;
+        let value = package_json.get("fta").cloned().unwrap_or(Value::Null);
+        return config_from_value(value, content, config_path);
+    }
+
+    match extension {
+        Some("yaml") | Some("yml") => {
+            let value: Value = serde_yaml_ng::from_str(content).map_err(|err| ConfigError {
+                message: format!("Invalid YAML in {}: {}", config_path, err),
+            })?;
+            config_from_value(value, content, config_path)
+        }
+        Some("toml") => {
+            let value: Value = toml::from_str(content).map_err(|err| ConfigError {
+                message: format!("Invalid TOML in {}: {}", config_path, err),
+            })?;
+            config_from_value(value, content, config_path)
+        }
+        _ => parse_config(content, config_path),
+    }
+}
+
+/// Validates a config that was parsed from a format other than plain JSON
+fn config_from_value(
+    value: Value,
+    content: &str,
+    config_path: &str,
+) -> Result<FtaConfigOptional, ConfigError> {
+    let value = match value {
+        Value::Null => Value::Object(Default::default()),
+        value => value,
+    };
+
+    check_options(&value, content, config_path)?;
+
+    let provided_config: FtaConfigOptional =
+        serde_json::from_value(value).map_err(|err| ConfigError {
+            message: format!("Invalid config in {}: {}", config_path, err),
+        })?;
+
+    validate_config(&provided_config, "").map_err(|message| ConfigError {
+        message: format!("Invalid config in {}: {}", config_path, message),
+    })?;
+
+    Ok(provided_config)
+}
+
+/// Strictly parses the content of an fta.json: syntax errors, unknown options, values of the
+/// wrong type and invalid values are all reported rather than ignored.
+pub fn parse_config(content: &str, config_path: &str) -> Result<FtaConfigOptional, ConfigError> {
+    let value: Value = serde_json::from_str(content).map_err(|err| ConfigError {
+        message: format!("Invalid JSON in {}: {}", config_path, err),
+    })?;
+
+    check_options(&value, content, config_path)?;
+
+    let provided_config: FtaConfigOptional =
+        serde_json::from_str(content).map_err(|err| ConfigError {
+            message: format!("Invalid config in {}: {}", config_path, err),
+        })?;
+
+    validate_config(&provided_config, "").map_err(|message| ConfigError {
+        message: format!("Invalid config in {}: {}", config_path, message),
+    })?;
+
+    Ok(provided_config)
+}
+
+/// Rejects unknown options, suggesting the closest known one
+fn check_options(value: &Value, content: &str, config_path: &str) -> Result<(), ConfigError> {
+    let object = value.as_object().ok_or_else(|| ConfigError {
+        message: format!("Invalid config in {}: expected a JSON object", config_path),
+    })?;
+
+    let mut options: Vec<(String, &str, Vec<&str>)> = object
+        .keys()
+        .map(|key| (key.clone(), key.as_str(), CONFIG_OPTIONS.to_vec()))
+        .collect();
+
+    if let Some(Value::Array(overrides)) = object.get("overrides") {
+        let mut known: Vec<&str> = OVERRIDE_SELECTORS.to_vec();
+        known.extend(
+            CONFIG_OPTIONS
+                .iter()
+                .filter(|option| **option != "overrides" && **option != "extends"),
+        );
+
+        for (index, config_override) in overrides.iter().enumerate() {
+            for key in config_override
+                .as_object()
+                .into_iter()
+                .flat_map(|o| o.keys())
+            {
+                let path = format!("overrides[{}].{}", index, key);
+                options.push((path, key.as_str(), known.clone()));
+            }
+        }
+    }
+
+    for (path, key, known) in options {
+        if known.contains(&key) {
+            continue;
+        }
+
+        let location = locate_key(content, key)
+            .map(|(line, column)| format!(" at line {} column {}", line, column))
+            .unwrap_or_default();
+        let suggestion = closest_match(key, &known)
+            .map(|option| format!(", did you mean \"{}\"?", option))
+            .unwrap_or_default();
+
+        return Err(ConfigError {
+            message: format!(
+                "Unknown option \"{}\" in {}{}{}",
+                path, config_path, location, suggestion
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks the values serde can't, prefixing option names with `prefix` in messages
+fn validate_config(config: &FtaConfigOptional, prefix: &str) -> Result<(), String> {
+    if config.extends.iter().flatten().any(String::is_empty) {
+        return Err(format!("{}extends: presets can't be empty", prefix));
+    }
+
+    for option in config.replace.iter().flatten() {
+        if !LIST_OPTIONS.contains(&option.as_str()) {
+            let suggestion = closest_match(option, &LIST_OPTIONS)
+                .map(|list_option| format!(", did you mean \"{}\"?", list_option))
+                .unwrap_or_default();
+            return Err(format!(
+                "{}replace: \"{}\" isn't one of {}{}",
+                prefix,
+                option,
+                LIST_OPTIONS.join(", "),
+                suggestion
+            ));
+        }
+    }
+
+    for extension in config.extensions.iter().flatten() {
+        if !extension.starts_with('.') || extension.len() < 2 {
+            return Err(format!(
+                "{}extensions: \"{}\" should be a file extension starting with a dot, e.g. \".ts\"",
+                prefix, extension
+            ));
+        }
+    }
+
+    for pattern in config.exclude_filenames.iter().flatten() {
+        if pattern.is_empty() {
+            return Err(format!(
+                "{}exclude_filenames: patterns can't be empty",
+                prefix
+            ));
+        }
+        if let Err(err) = Glob::new(pattern) {
+            return Err(format!("{}exclude_filenames: {}", prefix, err));
+        }
+    }
+
+    for pattern in config.exclude_directories.iter().flatten() {
+        if pattern.trim_start_matches('!').is_empty() {
+            return Err(format!(
+                "{}exclude_directories: patterns can't be empty",
+                prefix
+            ));
+        }
+        if let Err(err) = GitignoreBuilder::new("").add_line(None, pattern) {
+            return Err(format!("{}exclude_directories: {}", prefix, err));
+        }
+    }
+
+    if config.output_limit == Some(0) {
+        return Err(format!("{}output_limit: should be at least 1", prefix));
+    }
+    if config.max_file_size == Some(0) {
+        return Err(format!("{}max_file_size: should be at least 1", prefix));
+    }
+    if config.file_timeout_ms == Some(0) {
+        return Err(format!("{}file_timeout_ms: should be at least 1", prefix));
+    }
+
+    if let Some(target) = config
+        .parser
+        .as_ref()
+        .and_then(|parser| parser.target.as_ref())
+    {
+        if es_version(target).is_none() {
+            return Err(format!(
+                "{}parser.target: \"{}\" isn't an ECMAScript version, e.g. \"es2022\" or \"esnext\"",
+                prefix, target
+            ));
+        }
+    }
+
+    for (index, config_override) in config.overrides.iter().flatten().enumerate() {
+        let prefix = format!("{}overrides[{}].", prefix, index);
+
+        if config_override.files.is_none() && config_override.owners.is_none() {
+            return Err(format!(
+                "{}: an override needs a selector, one of {}",
+                prefix.trim_end_matches('.'),
+                OVERRIDE_SELECTORS.join(", ")
+            ));
+        }
+        if config_override.owners.as_ref().is_some_and(Vec::is_empty) {
+            return Err(format!("{}owners: should list at least one owner", prefix));
+        }
+        if config_override.files.as_ref().is_some_and(Vec::is_empty) {
+            return Err(format!("{}files: should list at least one pattern", prefix));
+        }
+        for pattern in config_override.files.iter().flatten() {
+            if let Err(err) = GlobBuilder::new(&override_glob(pattern)).build() {
+                return Err(format!("{}files: {}", prefix, err));
+            }
+        }
+
+        validate_config(&config_override.config, &prefix)?;
+    }
+
+    Ok(())
+}
+
+/// The 1-based line and column where `key` is first used as an object key in `content`
+fn locate_key(content: &str, key: &str) -> Option<(usize, usize)> {
+    let quoted = format!("\"{}\"", key);
+    let mut offset = 0;
+
+    while let Some(found) = content[offset..].find(&quoted) {
+        let start = offset + found;
+        offset = start + quoted.len();
+
+        if content[offset..].trim_start().starts_with(':') {
+            let line = content[..start].matches('\n').count() + 1;
+            let column = start - content[..start].rfind('\n').map_or(0, |i| i + 1) + 1;
+            return Some((line, column));
+        }
+    }
+
+    None
+}
+
+/// The known option closest to `key`, if any is a plausible typo of it
+fn closest_match<'a>(key: &str, known: &[&'a str]) -> Option<&'a str> {
+    known
+        .iter()
+        .map(|option| (edit_distance(key, option), *option))
+        .filter(|(distance, option)| *distance <= (option.len() / 3).max(2))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, option)| option)
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
@@ -1,8 +1,15 @@
 #[cfg(test)]
 mod tests {
-    use crate::config::read_config;
+    use crate::config::{
+        apply_overrides, apply_setting, discover_config, env_settings, find_config, format_config,
+        get_default_config, load_config, parse_setting, read_config, scope_overrides,
+        ConfigSources, ScoreLines,
+    };
+    use std::fs;
     use std::io::Write;
+    use std::path::Path;
     use tempfile::NamedTempFile;
+    use tempfile::TempDir;
 
     fn create_temp_file(content: &str) -> NamedTempFile {
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -35,6 +42,10 @@ mod tests {
                 ".jsx".to_string(),
                 ".ts".to_string(),
                 ".tsx".to_string(),
+                ".mjs".to_string(),
+                ".cjs".to_string(),
+                ".mts".to_string(),
+                ".cts".to_string(),
                 ".foo.ts".to_string()
             ]
         );
@@ -58,7 +69,7 @@ mod tests {
         );
         assert_eq!(config.output_limit, 2500);
         assert_eq!(config.score_cap, 500);
-        assert_eq!(config.include_comments, true);
+        assert!(config.include_comments);
     }
 
     #[test]
@@ -81,6 +92,10 @@ mod tests {
                 ".jsx".to_string(),
                 ".ts".to_string(),
                 ".tsx".to_string(),
+                ".mjs".to_string(),
+                ".cjs".to_string(),
+                ".mts".to_string(),
+                ".cts".to_string(),
                 ".foo.ts".to_string()
             ]
         );
@@ -103,7 +118,7 @@ mod tests {
         );
         assert_eq!(config.output_limit, 5000);
         assert_eq!(config.score_cap, 1000);
-        assert_eq!(config.include_comments, false);
+        assert!(!config.include_comments);
     }
 
     #[test]
@@ -119,6 +134,10 @@ mod tests {
                 ".jsx".to_string(),
                 ".ts".to_string(),
                 ".tsx".to_string(),
+                ".mjs".to_string(),
+                ".cjs".to_string(),
+                ".mts".to_string(),
+                ".cts".to_string(),
             ]
         );
         assert_eq!(
@@ -139,7 +158,7 @@ mod tests {
         );
         assert_eq!(config.output_limit, 5000);
         assert_eq!(config.score_cap, 1000);
-        assert_eq!(config.include_comments, false);
+        assert!(!config.include_comments);
     }
 
     #[test]
@@ -166,6 +185,10 @@ mod tests {
                 ".jsx".to_string(),
                 ".ts".to_string(),
                 ".tsx".to_string(),
+                ".mjs".to_string(),
+                ".cjs".to_string(),
+                ".mts".to_string(),
+                ".cts".to_string(),
                 ".foo.ts".to_string(),
             ]
         );
@@ -189,7 +212,7 @@ mod tests {
         );
         assert_eq!(config.output_limit, 2500);
         assert_eq!(config.score_cap, 500);
-        assert_eq!(config.include_comments, false);
+        assert!(!config.include_comments);
     }
 
     #[test]
@@ -198,4 +221,494 @@ mod tests {
 
         assert!(config.is_err(), "Expected error, got {:?}", config);
     }
+
+    #[test]
+    fn test_apply_overrides_matching_owners() {
+        let config = read_config(
+            create_temp_file(
+                r#"
+    {
+        "score_cap": 80,
+        "overrides": [
+            { "owners": ["@org/legacy"], "score_cap": 95, "exclude_directories": ["/old"] },
+            { "owners": ["@org/legacy", "@org/web"], "exclude_under": 20 }
+        ]
+    }
+    "#,
+            )
+            .path()
+            .to_str()
+            .unwrap()
+            .to_string(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(config.overrides.len(), 2);
+
+        assert!(apply_overrides(&config, "a.ts", &["@org/api".to_string()]).is_none());
+
+        let legacy = apply_overrides(&config, "a.ts", &["@org/legacy".to_string()]).unwrap();
+        assert_eq!(legacy.score_cap, 95);
+        assert_eq!(legacy.exclude_under, 20);
+        assert!(legacy.exclude_directories.contains(&"/old".to_string()));
+
+        let web = apply_overrides(&config, "a.ts", &["@org/web".to_string()]).unwrap();
+        assert_eq!(web.score_cap, 80);
+        assert_eq!(web.exclude_under, 20);
+
+        assert!(
+            apply_overrides(&get_default_config(), "a.ts", &["@org/web".to_string()]).is_none()
+        );
+    }
+
+    fn read_config_error(content: &str) -> String {
+        let temp_file = create_temp_file(content);
+        let path = temp_file.path().to_str().unwrap();
+
+        read_config(path.to_string(), true).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_read_config_reports_syntax_errors() {
+        let error = read_config_error("{\n  \"score_cap\": 50,\n}");
+
+        assert!(
+            error.starts_with("ConfigError! Invalid JSON in "),
+            "{}",
+            error
+        );
+        assert!(
+            error.ends_with("trailing comma at line 3 column 1"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_read_config_rejects_unknown_options() {
+        let error = read_config_error("{\n  \"output_limit\": 50,\n  \"score_capp\": 50\n}");
+        assert!(
+            error.contains("Unknown option \"score_capp\" in ",),
+            "{}",
+            error
+        );
+        assert!(
+            error.ends_with(" at line 3 column 3, did you mean \"score_cap\"?"),
+            "{}",
+            error
+        );
+
+        let error = read_config_error(r#"{ "overrides": [{ "owner": ["@org/web"] }] }"#);
+        assert!(
+            error.contains("Unknown option \"overrides[0].owner\""),
+            "{}",
+            error
+        );
+        assert!(error.ends_with("did you mean \"owners\"?"), "{}", error);
+
+        let error = read_config_error(r#"{ "colour": "red" }"#);
+        assert!(!error.contains("did you mean"), "{}", error);
+    }
+
+    #[test]
+    fn test_read_config_rejects_invalid_values() {
+        let error = read_config_error(r#"{ "score_cap": -1 }"#);
+        assert!(error.contains("invalid value: integer `-1`"), "{}", error);
+        assert!(error.ends_with("at line 1 column 17"), "{}", error);
+
+        let error = read_config_error(r#"{ "include_comments": "yes" }"#);
+        assert!(error.contains("invalid type: string \"yes\""), "{}", error);
+
+        let error = read_config_error(r#"{ "output_limit": 0 }"#);
+        assert!(
+            error.ends_with("output_limit: should be at least 1"),
+            "{}",
+            error
+        );
+
+        let error = read_config_error(r#"{ "extensions": ["ts"] }"#);
+        assert!(error.contains("extensions: \"ts\" should be"), "{}", error);
+
+        let error = read_config_error(r#"{ "exclude_filenames": ["*.{ts"] }"#);
+        assert!(error.contains("exclude_filenames: "), "{}", error);
+
+        let error = read_config_error(r#"{ "overrides": [{ "score_cap": 10 }] }"#);
+        assert!(
+            error.ends_with("overrides[0]: an override needs a selector, one of files, owners"),
+            "{}",
+            error
+        );
+
+        let error =
+            read_config_error(r#"{ "overrides": [{ "owners": ["@a"], "output_limit": 0 }] }"#);
+        assert!(
+            error.ends_with("overrides[0].output_limit: should be at least 1"),
+            "{}",
+            error
+        );
+
+        let error = read_config_error(r#"{ "parser": { "target": "es2099" } }"#);
+        assert!(
+            error.contains("parser.target: \"es2099\" isn't an ECMAScript version"),
+            "{}",
+            error
+        );
+
+        let error = read_config_error(r#"{ "parser": { "decorator": true } }"#);
+        assert!(error.contains("unknown field `decorator`"), "{}", error);
+
+        let error = read_config_error(r#"{ "score_lines": "sloc" }"#);
+        assert!(
+            error.contains(
+                "unknown variant `sloc`, expected one of `source`, `physical`, `logical`"
+            ),
+            "{}",
+            error
+        );
+
+        let error = read_config_error(r#"{ "encoding": "utf-16" }"#);
+        assert!(
+            error.contains(
+                "unknown variant `utf-16`, expected one of `utf-8`, `latin1`, `windows-1252`"
+            ),
+            "{}",
+            error
+        );
+
+        let error = read_config_error(r#"{ "max_file_size": 0 }"#);
+        assert!(
+            error.ends_with("max_file_size: should be at least 1"),
+            "{}",
+            error
+        );
+
+        let error = read_config_error(r#"{ "file_timeout_ms": 0 }"#);
+        assert!(
+            error.ends_with("file_timeout_ms: should be at least 1"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_read_config_reports_unreadable_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+
+        let error = read_config(path, true).unwrap_err().to_string();
+
+        assert!(
+            error.starts_with("ConfigError! Unable to read config file"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_find_config_walks_up_parent_directories() {
+        let dir = TempDir::new().unwrap();
+        let nested = dir.path().join("a/b/c");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(dir.path().join("fta.toml"), "score_cap = 10").unwrap();
+        fs::write(
+            dir.path().join("a/package.json"),
+            r#"{ "name": "a", "fta": { "score_cap": 20 } }"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("a/b/package.json"), r#"{ "name": "b" }"#).unwrap();
+
+        let found = find_config(&nested).unwrap();
+        assert!(found.ends_with("a/package.json"), "{}", found.display());
+
+        fs::write(dir.path().join("a/fta.yaml"), "score_cap: 30").unwrap();
+        fs::write(dir.path().join("a/.ftarc.json"), r#"{ "score_cap": 40 }"#).unwrap();
+        let found = find_config(&nested).unwrap();
+        assert!(found.ends_with("a/.ftarc.json"), "{}", found.display());
+
+        let (config, sources) = discover_config(&nested, None).unwrap();
+        assert_eq!(config.score_cap, 40);
+        assert_eq!(sources.get("score_cap"), [found.display().to_string()]);
+        assert_eq!(sources.get("output_limit"), ["default"]);
+    }
+
+    #[test]
+    fn test_read_config_formats() {
+        let dir = TempDir::new().unwrap();
+        let config_files = [
+            ("fta.yaml", "score_cap: 10\nextensions:\n  - .vue\n"),
+            ("fta.toml", "score_cap = 10\nextensions = [\".vue\"]\n"),
+            (
+                "package.json",
+                r#"{ "fta": { "score_cap": 10, "extensions": [".vue"] } }"#,
+            ),
+        ];
+
+        for (file_name, content) in config_files {
+            let path = dir.path().join(file_name);
+            fs::write(&path, content).unwrap();
+
+            let config = read_config(path.display().to_string(), true).unwrap();
+            assert_eq!(config.score_cap, 10, "{}", file_name);
+            assert_eq!(config.extensions.last().unwrap(), ".vue", "{}", file_name);
+        }
+
+        let path = dir.path().join("fta.toml");
+        fs::write(&path, "score_capp = 10").unwrap();
+        let error = read_config(path.display().to_string(), true)
+            .unwrap_err()
+            .to_string();
+        assert!(error.ends_with("did you mean \"score_cap\"?"), "{}", error);
+
+        fs::write(&path, "score_cap = ").unwrap();
+        let error = read_config(path.display().to_string(), true)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Invalid TOML in"), "{}", error);
+    }
+
+    #[test]
+    fn test_format_config_lists_sources() {
+        let temp_file = create_temp_file(r#"{ "score_cap": 50, "extensions": [".vue"] }"#);
+        let path = temp_file.path().to_str().unwrap().to_string();
+
+        let (config, mut sources) = discover_config(Path::new(""), Some(path.clone())).unwrap();
+        sources.set("exclude_under", "--exclude-under");
+        let output = format_config(&config, &sources);
+
+        assert!(output.contains(&format!("score_cap: 50\n    from {}", path)));
+        assert!(output.contains(&format!(
+            "extensions: [\".js\",\".jsx\",\".ts\",\".tsx\",\".mjs\",\".cjs\",\".mts\",\".cts\",\".vue\"]\n    from default, {}",
+            path
+        )));
+        assert!(output.contains("exclude_under: 6\n    from --exclude-under"));
+        assert!(output.contains("output_limit: 5000\n    from default"));
+
+        assert!(discover_config(Path::new(""), Some("missing.json".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_apply_overrides_matching_files() {
+        let temp_file = create_temp_file(
+            r#"
+    {
+        "overrides": [
+            { "files": ["*.test.ts", "tests/**"], "score_cap": 100 },
+            { "files": ["/src/generated/**"], "exclude_under": 1000 },
+            { "files": ["src/**"], "owners": ["@org/core"], "score_cap": 40 }
+        ]
+    }
+    "#,
+        );
+        let config = read_config(temp_file.path().to_str().unwrap().to_string(), true).unwrap();
+        let core = vec!["@org/core".to_string()];
+
+        assert_eq!(
+            apply_overrides(&config, "src/a/b.test.ts", &[])
+                .unwrap()
+                .score_cap,
+            100
+        );
+        assert_eq!(
+            apply_overrides(&config, "tests/x.ts", &[])
+                .unwrap()
+                .score_cap,
+            100
+        );
+        assert_eq!(
+            apply_overrides(&config, "src/generated/api.ts", &[])
+                .unwrap()
+                .exclude_under,
+            1000
+        );
+        assert!(apply_overrides(&config, "lib/src/generated/api.ts", &[]).is_none());
+        assert!(apply_overrides(&config, "src/index.ts", &[]).is_none());
+        assert_eq!(
+            apply_overrides(&config, "src/index.ts", &core)
+                .unwrap()
+                .score_cap,
+            40
+        );
+        assert!(apply_overrides(&config, "lib/index.ts", &core).is_none());
+    }
+
+    #[test]
+    fn test_scope_overrides() {
+        let temp_file = create_temp_file(
+            r#"{ "overrides": [{ "files": ["*.test.ts", "/fixtures/**"], "score_cap": 100 }] }"#,
+        );
+        let provided = load_config(temp_file.path().to_str().unwrap()).unwrap();
+        let scoped = scope_overrides(provided, "packages/a");
+
+        assert_eq!(
+            scoped.overrides.unwrap()[0].files,
+            Some(vec![
+                "packages/a/**/*.test.ts".to_string(),
+                "packages/a/fixtures/**".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_read_config_extends_presets() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            "node_modules/@org/fta-config/package.json",
+            r#"{ "name": "@org/fta-config", "fta": { "score_cap": 60, "extensions": [".vue"] } }"#,
+        );
+        write(
+            "node_modules/@org/fta-config/strict.yaml",
+            "extends: ./package.json\nscore_cap: 40\n",
+        );
+        write(
+            "config/base.json",
+            r#"{ "exclude_under": 3, "extensions": [".svelte"] }"#,
+        );
+        write(
+            "project/fta.json",
+            r#"{ "extends": ["../config/base.json", "@org/fta-config/strict.yaml"], "output_limit": 10 }"#,
+        );
+
+        let config_path = root.join("project/fta.json").display().to_string();
+        let config = read_config(config_path.clone(), true).unwrap();
+        assert_eq!(config.score_cap, 40);
+        assert_eq!(config.exclude_under, 3);
+        assert_eq!(config.output_limit, 10);
+        assert_eq!(
+            &config.extensions[8..],
+            [".svelte".to_string(), ".vue".to_string()]
+        );
+
+        let (_, sources) = discover_config(&root.join("project"), None).unwrap();
+        let score_cap_source = &sources.get("score_cap")[0];
+        assert!(
+            score_cap_source.ends_with("strict.yaml"),
+            "{}",
+            score_cap_source
+        );
+        assert_eq!(sources.get("extensions").len(), 3);
+        assert_eq!(sources.get("output_limit"), [config_path]);
+
+        write("project/fta.json", r#"{ "extends": "@org/missing" }"#);
+        let error = read_config(root.join("project/fta.json").display().to_string(), true)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("Unable to find the config \"@org/missing\" extended by"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_read_config_rejects_circular_extends() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.json"), r#"{ "extends": "./b.json" }"#).unwrap();
+        fs::write(dir.path().join("b.json"), r#"{ "extends": "./a.json" }"#).unwrap();
+
+        let error = read_config(dir.path().join("a.json").display().to_string(), true)
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("Circular extends: "), "{}", error);
+        assert!(error.ends_with("a.json"), "{}", error);
+    }
+
+    #[test]
+    fn test_read_config_replaces_lists() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("base.json"),
+            r#"{ "extensions": [".vue"], "exclude_directories": ["/vendor"] }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("fta.json"),
+            r#"{
+                "extends": "./base.json",
+                "replace": ["exclude_directories"],
+                "exclude_directories": ["/dist", "!/dist/keep"],
+                "extensions": [".svelte"]
+            }"#,
+        )
+        .unwrap();
+
+        let (config, sources) = discover_config(dir.path(), None).unwrap();
+        assert_eq!(config.exclude_directories, vec!["/dist", "!/dist/keep"]);
+        assert_eq!(&config.extensions[8..], [".vue", ".svelte"]);
+        assert_eq!(sources.get("exclude_directories").len(), 1);
+        assert_eq!(sources.get("extensions").len(), 3);
+
+        let error = read_config_error(r#"{ "replace": ["extension"] }"#);
+        assert!(
+            error.ends_with(
+                "replace: \"extension\" isn't one of extensions, exclude_filenames, exclude_directories, overrides, did you mean \"extensions\"?"
+            ),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_settings_set_and_add_to_options() {
+        let mut sources = ConfigSources::default();
+        let mut config = get_default_config();
+        for setting in [
+            "score_cap=70",
+            "include_comments=true",
+            "score_lines=logical",
+            "extensions+=.vue,.svelte",
+            "exclude_directories=/dist",
+            r#"overrides={"files": ["*.test.ts"], "score_cap": 90}"#,
+        ] {
+            let provided_config = parse_setting(setting, "--set").unwrap();
+            config = apply_setting(&config, &mut sources, provided_config, "--set").unwrap();
+        }
+
+        assert_eq!(config.score_cap, 70);
+        assert!(config.include_comments);
+        assert_eq!(config.score_lines, ScoreLines::Logical);
+        assert_eq!(&config.extensions[8..], [".vue", ".svelte"]);
+        assert_eq!(config.exclude_directories, vec!["/dist"]);
+        assert_eq!(config.overrides[0].config.score_cap, Some(90));
+        assert_eq!(sources.get("score_cap"), ["--set"]);
+        assert_eq!(sources.get("extensions"), ["default", "--set"]);
+        assert_eq!(sources.get("exclude_directories"), ["--set"]);
+
+        let error = |setting: &str| parse_setting(setting, "--set").unwrap_err().to_string();
+        assert!(error("score_cap").contains("expected option=value"));
+        assert!(error("score_cap+=1").contains("only list options"));
+        assert!(error("score_cap=high").contains("invalid type"));
+        assert!(error("extension=.ts").contains("did you mean \"extensions\"?"));
+        assert!(error("extensions=ts").contains("should be a file extension"));
+    }
+
+    #[test]
+    fn test_env_settings() {
+        let vars = [
+            ("FTA_SCORE_CAP", "70"),
+            ("FTA_EXTENSIONS", "+.vue"),
+            ("FTA_EXCLUDE_UNDER", "+3"),
+            ("FTA_SCORECAP", "70"),
+            ("PATH", "/usr/bin"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+
+        assert_eq!(
+            env_settings(vars),
+            vec![
+                (
+                    "exclude_under=+3".to_string(),
+                    "FTA_EXCLUDE_UNDER".to_string()
+                ),
+                ("extensions+=.vue".to_string(), "FTA_EXTENSIONS".to_string()),
+                ("score_cap=70".to_string(), "FTA_SCORE_CAP".to_string()),
+            ]
+        );
+    }
 }
@@ -73,6 +73,6 @@ impl Visit for ComplexityVisitor {
 
 pub fn cyclomatic_complexity(module: &Module) -> usize {
     let mut visitor = ComplexityVisitor::new();
-    visitor.visit_module(&module);
+    visitor.visit_module(module);
     visitor.complexity
 }
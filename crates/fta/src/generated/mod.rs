@@ -0,0 +1,135 @@
+mod tests;
+
+/// Phrases in the header comment of a file that mark it as generated, lowercase. A bare "do not
+/// edit" isn't one of them, as licence headers say so too.
+const GENERATED_MARKERS: [&str; 8] = [
+    "@generated",
+    "auto-generated",
+    "autogenerated",
+    "automatically generated",
+    "generated by the protocol buffer compiler",
+    "protoc-gen-",
+    "graphql-codegen",
+    "graphql code generator",
+];
+
+/// Directories codegen tools write to, e.g. Relay and Apollo
+const GENERATED_DIRECTORIES: [&str; 1] = ["__generated__"];
+
+/// Parts of the names of generated files, e.g. `schema.generated.ts` or `api_pb.js`
+const GENERATED_NAME_PARTS: [&str; 3] = [".generated.", ".gen.", "_pb."];
+
+/// Minified files average longer lines than this
+const MINIFIED_AVERAGE_LINE_LENGTH: usize = 200;
+
+/// Files with a line longer than this and little whitespace are minified
+const MINIFIED_MAX_LINE_LENGTH: usize = 1000;
+const MINIFIED_WHITESPACE_RATIO: f64 = 0.1;
+
+/// Why a file looks generated or minified, if it does: from its path, a marker in its header
+/// comment or the length of its lines.
+pub fn detect(file_name: &str, source: &str) -> Option<String> {
+    if let Some(reason) = generated_path(file_name) {
+        return Some(reason);
+    }
+    if let Some(marker) = generated_marker(source) {
+        return Some(format!("generated, its header says \"{}\"", marker));
+    }
+
+    minified(source)
+}
+
+fn generated_path(file_name: &str) -> Option<String> {
+    let mut components: Vec<&str> = file_name.split(['/', '\\']).collect();
+    let name = components.pop().unwrap_or_default();
+
+    if let Some(directory) = components
+        .iter()
+        .find(|component| GENERATED_DIRECTORIES.contains(component))
+    {
+        return Some(format!("generated, it's in a {} directory", directory));
+    }
+    GENERATED_NAME_PARTS
+        .iter()
+        .find(|part| name.contains(*part))
+        .map(|part| format!("generated, its name has \"{}\"", part))
+}
+
+/// The first marker found in the comments at the top of the file, as written there
+fn generated_marker(source: &str) -> Option<&str> {
+    let mut in_block_comment = false;
+
+    for line in source.lines() {
+        let line = line.trim();
+        let is_comment = in_block_comment
+            || line.is_empty()
+            || line.starts_with("//")
+            || line.starts_with("/*")
+            || line.starts_with("#!");
+        if !is_comment {
+            return None;
+        }
+        if line.starts_with("/*") {
+            in_block_comment = true;
+        }
+        if in_block_comment && line.contains("*/") {
+            in_block_comment = false;
+        }
+
+        if let Some(marker) = codegen_header(line) {
+            return Some(marker);
+        }
+        let lowercase = line.to_ascii_lowercase();
+        for marker in GENERATED_MARKERS {
+            if let Some(index) = lowercase.find(marker) {
+                return Some(&line[index..index + marker.len()]);
+            }
+        }
+    }
+
+    None
+}
+
+/// The `Code generated ... DO NOT EDIT.` line many codegen tools start their output with, if `line`
+/// is one
+fn codegen_header(line: &str) -> Option<&str> {
+    let start = line.find("Code generated ")?;
+    let end = line[start..].find("DO NOT EDIT")? + start + "DO NOT EDIT".len();
+    let is_comment_start = line[..start]
+        .chars()
+        .all(|c| c.is_whitespace() || "/*".contains(c));
+
+    is_comment_start.then(|| &line[start..end])
+}
+
+fn minified(source: &str) -> Option<String> {
+    let lengths: Vec<usize> = source
+        .lines()
+        .map(|line| line.trim().chars().count())
+        .filter(|&length| length > 0)
+        .collect();
+    if lengths.is_empty() {
+        return None;
+    }
+
+    let average = lengths.iter().sum::<usize>() / lengths.len();
+    if average > MINIFIED_AVERAGE_LINE_LENGTH {
+        return Some(format!(
+            "minified, its lines average {} characters",
+            average
+        ));
+    }
+
+    let longest = lengths.iter().copied().max().unwrap_or_default();
+    let whitespace = source.chars().filter(|c| c.is_whitespace()).count();
+    let whitespace_ratio = whitespace as f64 / source.chars().count() as f64;
+    if longest > MINIFIED_MAX_LINE_LENGTH && whitespace_ratio < MINIFIED_WHITESPACE_RATIO {
+        return Some(format!(
+            "minified, it has a {} character line and {:.0}% whitespace",
+            longest,
+            whitespace_ratio * 100.0
+        ));
+    }
+
+    None
+}
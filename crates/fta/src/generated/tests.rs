@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use crate::generated::detect;
+
+    const SOURCE: &str = r#"import { add } from "./math";
+
+export function total(values: number[]): number {
+    return values.reduce(add, 0);
+}
+"#;
+
+    #[test]
+    fn test_handwritten_code_is_not_detected() {
+        assert_eq!(detect("src/total.ts", SOURCE), None);
+
+        // Markers only count in the header comment
+        let source = format!("{}// Do not edit the values above\n", SOURCE);
+        assert_eq!(detect("src/total.ts", &source), None);
+
+        // Licence headers asking not to edit them aren't codegen headers
+        let source = format!(
+            "/*\n * Copyright (c) Example Ltd.\n * DO NOT MODIFY OR REMOVE THIS NOTICE.\n * Do not edit without permission.\n */\n{}",
+            SOURCE
+        );
+        assert_eq!(detect("src/total.ts", &source), None);
+    }
+
+    #[test]
+    fn test_generated_markers() {
+        let headers = [
+            ("// @generated\n", "@generated"),
+            (
+                "// Code generated by protoc-gen-ts_proto. DO NOT EDIT.\n",
+                "Code generated by protoc-gen-ts_proto. DO NOT EDIT",
+            ),
+            (
+                "/* eslint-disable */\n/**\n * Generated by GraphQL Code Generator\n */\n",
+                "GraphQL Code Generator",
+            ),
+            (
+                "#!/usr/bin/env node\n\n// This file was automatically generated.\n",
+                "automatically generated",
+            ),
+        ];
+
+        for (header, marker) in headers {
+            assert_eq!(
+                detect("src/total.ts", &format!("{}{}", header, SOURCE)),
+                Some(format!("generated, its header says \"{}\"", marker)),
+                "{}",
+                header
+            );
+        }
+    }
+
+    #[test]
+    fn test_generated_paths() {
+        assert_eq!(
+            detect("src/__generated__/query.graphql.ts", SOURCE),
+            Some("generated, it's in a __generated__ directory".to_string())
+        );
+        assert_eq!(
+            detect("src/api_pb.js", SOURCE),
+            Some("generated, its name has \"_pb.\"".to_string())
+        );
+        assert_eq!(detect("src/generated.ts", SOURCE), None);
+    }
+
+    #[test]
+    fn test_minified_code() {
+        let statement = "var a=function(b,c){return b+c};";
+        let minified = statement.repeat(50);
+        assert_eq!(
+            detect("vendor/lib.js", &minified),
+            Some("minified, its lines average 1600 characters".to_string())
+        );
+
+        // A long line among short ones, with little whitespace overall
+        let source = format!("{}\n{}", "a;\n".repeat(20), statement.repeat(40));
+        assert_eq!(
+            detect("vendor/lib.js", &source),
+            Some("minified, it has a 1280 character line and 8% whitespace".to_string())
+        );
+
+        // A long line in otherwise readable code
+        let source = format!(
+            "{}\nconst data = \"{}\";\n",
+            SOURCE.repeat(30),
+            "x".repeat(1200)
+        );
+        assert_eq!(detect("src/data.ts", &source), None);
+    }
+}
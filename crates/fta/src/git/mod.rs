@@ -0,0 +1,227 @@
+use crate::config::ConfigError;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+mod tests;
+
+#[derive(Debug, Clone)]
+pub struct GitError {
+    message: String,
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GitError! {}", self.message)
+    }
+}
+
+impl GitError {
+    fn new(message: String) -> Self {
+        GitError { message }
+    }
+}
+
+/// An invalid config file read from a tree
+impl From<ConfigError> for GitError {
+    fn from(err: ConfigError) -> Self {
+        GitError::new(err.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    pub id: String,
+    pub date: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeEntry {
+    pub path: String,
+    pub object_id: String,
+}
+
+/// A local git repository (bare or not), accessed through the `git` executable.
+/// Nothing here touches the working tree: files are read straight from the object store.
+pub struct GitRepository {
+    path: String,
+}
+
+impl GitRepository {
+    pub fn open(path: &str) -> Result<GitRepository, GitError> {
+        let repository = GitRepository {
+            path: path.to_string(),
+        };
+        repository.run(&["rev-parse", "--git-dir"])?;
+
+        Ok(repository)
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new("git");
+        command.arg("-C").arg(&self.path);
+        command
+    }
+
+    fn run(&self, args: &[&str]) -> Result<Vec<u8>, GitError> {
+        let output = self
+            .command()
+            .args(args)
+            .output()
+            .map_err(|err| GitError::new(format!("Unable to run git: {}", err)))?;
+
+        if !output.status.success() {
+            return Err(GitError::new(format!(
+                "`git {}` failed in {}: {}",
+                args.join(" "),
+                self.path,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(output.stdout)
+    }
+
+    /// Resolves a commit-ish (sha, branch, tag, `HEAD~3`...) to a full commit id.
+    pub fn resolve_commit(&self, rev: &str) -> Result<String, GitError> {
+        let output = self
+            .run(&[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{}^{{commit}}", rev),
+            ])
+            .map_err(|_| GitError::new(format!("Unknown revision {} in {}", rev, self.path)))?;
+
+        Ok(String::from_utf8_lossy(&output).trim().to_string())
+    }
+
+    fn log(&self, args: &[&str]) -> Result<Vec<Commit>, GitError> {
+        let mut log_args = vec!["log", "--first-parent", "--reverse", "--format=%H %cI"];
+        log_args.extend_from_slice(args);
+        let output = self.run(&log_args)?;
+
+        Ok(String::from_utf8_lossy(&output)
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .map(|(id, date)| Commit {
+                id: id.to_string(),
+                date: date.to_string(),
+            })
+            .collect())
+    }
+
+    /// Lists the first-parent commits from `since` up to and including `HEAD`, oldest first.
+    /// `since` is treated as a commit-ish if it resolves to one, otherwise as a date.
+    pub fn commits_since(&self, since: &str) -> Result<Vec<Commit>, GitError> {
+        match self.resolve_commit(since) {
+            Ok(since_id) => {
+                let mut commits = self.log(&["-1", &since_id])?;
+                commits.extend(self.log(&[&format!("{}..HEAD", since_id)])?);
+                Ok(commits)
+            }
+            Err(_) => self.log(&[&format!("--since={}", since), "HEAD"]),
+        }
+    }
+
+    /// Lists every blob in the tree of `rev`, recursively. When the repository was opened from a
+    /// subdirectory of its working tree, only the blobs under it are listed, with paths relative to
+    /// it like a walk of that directory would report them.
+    pub fn list_files(&self, rev: &str) -> Result<Vec<TreeEntry>, GitError> {
+        let output = self.run(&["ls-tree", "-r", "-z", rev])?;
+
+        Ok(output
+            .split(|byte| *byte == 0)
+            .filter_map(|record| {
+                // Format: <mode> SP <type> SP <object> TAB <path>
+                let record = std::str::from_utf8(record).ok()?;
+                let (meta, path) = record.split_once('\t')?;
+                let mut fields = meta.split(' ');
+                let object_type = fields.nth(1)?;
+                let object_id = fields.next()?;
+
+                if object_type != "blob" {
+                    return None;
+                }
+
+                Some(TreeEntry {
+                    path: path.to_string(),
+                    object_id: object_id.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    pub fn blob_reader(&self) -> Result<BlobReader, GitError> {
+        let mut child = self
+            .command()
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| GitError::new(format!("Unable to run git: {}", err)))?;
+
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+
+        Ok(BlobReader {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+}
+
+/// Reads blobs one at a time through a single long-running `git cat-file --batch` process.
+pub struct BlobReader {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl BlobReader {
+    /// Reads a blob along with its size, leaving out the content of blobs larger than `max_size`
+    /// so that they're never held in memory.
+    pub fn read(
+        &mut self,
+        object_id: &str,
+        max_size: u64,
+    ) -> Result<(u64, Option<Vec<u8>>), GitError> {
+        let io_error = |err: std::io::Error| GitError::new(format!("Unable to read blob: {}", err));
+
+        writeln!(self.stdin, "{}", object_id).map_err(io_error)?;
+        self.stdin.flush().map_err(io_error)?;
+
+        // Header format: <object> SP <type> SP <size> LF, or <object> SP missing LF
+        let mut header = String::new();
+        self.stdout.read_line(&mut header).map_err(io_error)?;
+        let size = match header.split_whitespace().collect::<Vec<_>>()[..] {
+            [_, "blob", size] => size.parse::<u64>().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| GitError::new(format!("{} is not a blob: {}", object_id, header.trim())))?;
+
+        // The content is followed by a trailing LF
+        if size > max_size {
+            io::copy(&mut (&mut self.stdout).take(size + 1), &mut io::sink()).map_err(io_error)?;
+            return Ok((size, None));
+        }
+        let mut content = vec![0; size as usize + 1];
+        self.stdout.read_exact(&mut content).map_err(io_error)?;
+        content.truncate(size as usize);
+
+        Ok((size, Some(content)))
+    }
+}
+
+impl Drop for BlobReader {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
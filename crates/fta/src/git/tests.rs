@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use crate::git::{BlobReader, GitRepository, TreeEntry};
+    use crate::test_utils::git;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_repository() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path();
+        git(repo, &["init", "--quiet"]);
+        fs::write(repo.join("a.ts"), "const a = 1;\n").unwrap();
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "--quiet", "-m", "first"]);
+        fs::create_dir(repo.join("nested dir")).unwrap();
+        fs::write(repo.join("nested dir/b.ts"), "const b = 2;\n").unwrap();
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "--quiet", "-m", "second"]);
+        dir
+    }
+
+    #[test]
+    fn test_commits_since_ref() {
+        let dir = create_repository();
+        let repository = GitRepository::open(dir.path().to_str().unwrap()).unwrap();
+
+        let commits = repository.commits_since("HEAD~1").unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[1].id, repository.resolve_commit("HEAD").unwrap());
+
+        let commits = repository.commits_since("HEAD").unwrap();
+        assert_eq!(commits.len(), 1);
+    }
+
+    #[test]
+    fn test_commits_since_date() {
+        let dir = create_repository();
+        let repository = GitRepository::open(dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(repository.commits_since("1970-01-02").unwrap().len(), 2);
+        assert_eq!(repository.commits_since("2090-01-01").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_list_files_and_read_blobs() {
+        let dir = create_repository();
+        let repository = GitRepository::open(dir.path().to_str().unwrap()).unwrap();
+
+        let files = repository.list_files("HEAD").unwrap();
+        let paths: Vec<&str> = files.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.ts", "nested dir/b.ts"]);
+        assert_eq!(repository.list_files("HEAD~1").unwrap().len(), 1);
+
+        let mut blobs = repository.blob_reader().unwrap();
+        let read = |blobs: &mut BlobReader, entry: &TreeEntry| {
+            blobs.read(&entry.object_id, u64::MAX).unwrap().1.unwrap()
+        };
+        assert_eq!(read(&mut blobs, &files[1]), b"const b = 2;\n");
+        assert_eq!(read(&mut blobs, &files[0]), b"const a = 1;\n");
+        assert!(blobs
+            .read("0000000000000000000000000000000000000000", u64::MAX)
+            .is_err());
+
+        // Blobs over the limit are skipped without reading them, leaving the next one intact
+        assert_eq!(blobs.read(&files[0].object_id, 5).unwrap(), (13, None));
+        assert_eq!(
+            blobs.read(&files[1].object_id, 13).unwrap(),
+            (13, Some(b"const b = 2;\n".to_vec()))
+        );
+    }
+
+    #[test]
+    fn test_list_files_in_a_subdirectory() {
+        let dir = create_repository();
+        let subdirectory = dir.path().join("nested dir");
+        let repository = GitRepository::open(subdirectory.to_str().unwrap()).unwrap();
+
+        let files = repository.list_files("HEAD").unwrap();
+        let paths: Vec<&str> = files.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(paths, vec!["b.ts"]);
+        assert!(repository.list_files("HEAD~1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bare_repository() {
+        let dir = create_repository();
+        let bare = TempDir::new().unwrap();
+        let source = dir.path().to_str().unwrap();
+        git(
+            bare.path(),
+            &["clone", "--quiet", "--bare", source, "repo.git"],
+        );
+
+        let repository =
+            GitRepository::open(bare.path().join("repo.git").to_str().unwrap()).unwrap();
+        assert_eq!(repository.list_files("HEAD").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_unknown_revision() {
+        let dir = create_repository();
+        let repository = GitRepository::open(dir.path().to_str().unwrap()).unwrap();
+
+        assert!(repository.resolve_commit("does-not-exist").is_err());
+        assert!(repository.list_files("does-not-exist").is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use crate::structs::{FileData, GroupSummary};
+use std::collections::BTreeMap;
+
+mod tests;
+
+/// Number of worst-scoring files listed for each group
+const WORST_FILES_LIMIT: usize = 3;
+
+/// Rolls files up into groups keyed by `key`, e.g. their package. A file may belong to several
+/// groups; files without any group are collected under `fallback`. Files that were skipped, failed
+/// to analyze or failed to parse are only counted as such. Groups are sorted by weighted score,
+/// worst first.
+pub fn summarize_groups<F>(file_data_list: &[FileData], key: F, fallback: &str) -> Vec<GroupSummary>
+where
+    F: Fn(&FileData) -> Vec<String>,
+{
+    let mut groups: BTreeMap<String, Vec<&FileData>> = BTreeMap::new();
+
+    for file_data in file_data_list {
+        let mut names = key(file_data);
+        if names.is_empty() {
+            names.push(fallback.to_string());
+        }
+        for name in names {
+            groups.entry(name).or_default().push(file_data);
+        }
+    }
+
+    let mut summaries: Vec<GroupSummary> = groups
+        .into_iter()
+        .map(|(name, files)| {
+            let (skipped, files): (Vec<&FileData>, Vec<&FileData>) =
+                files.into_iter().partition(|data| data.skipped.is_some());
+            let (analysis_failures, files): (Vec<&FileData>, Vec<&FileData>) = files
+                .into_iter()
+                .partition(|data| data.analysis_failure.is_some());
+            let (parse_failures, mut files): (Vec<&FileData>, Vec<&FileData>) = files
+                .into_iter()
+                .partition(|data| data.parse_failure.is_some());
+            let file_count = files.len();
+            let line_count: usize = files.iter().map(|data| data.line_count).sum();
+            let total_score: f64 = files.iter().map(|data| data.fta_score).sum();
+            let weighted_total: f64 = files
+                .iter()
+                .map(|data| data.fta_score * data.line_count as f64)
+                .sum();
+
+            files.sort_by(|a, b| b.fta_score.total_cmp(&a.fta_score));
+
+            GroupSummary {
+                name,
+                file_count,
+                line_count,
+                average_fta_score: if file_count > 0 {
+                    total_score / file_count as f64
+                } else {
+                    0.0
+                },
+                weighted_fta_score: if line_count > 0 {
+                    weighted_total / line_count as f64
+                } else {
+                    0.0
+                },
+                worst_files: files
+                    .iter()
+                    .take(WORST_FILES_LIMIT)
+                    .map(|data| data.file_name.clone())
+                    .collect(),
+                parse_failures: parse_failures.len(),
+                analysis_failures: analysis_failures.len(),
+                skipped: skipped.len(),
+            }
+        })
+        .collect();
+
+    summaries.sort_by(|a, b| b.weighted_fta_score.total_cmp(&a.weighted_fta_score));
+
+    summaries
+}
+
+pub fn group_by_package(file_data_list: &[FileData]) -> Vec<GroupSummary> {
+    summarize_groups(
+        file_data_list,
+        |data| data.package.iter().cloned().collect(),
+        "(no package)",
+    )
+}
+
+/// Rolls files up by their CODEOWNERS owners. Files with several owners count towards each of them.
+pub fn group_by_owner(file_data_list: &[FileData]) -> Vec<GroupSummary> {
+    summarize_groups(file_data_list, |data| data.owners.clone(), "(unowned)")
+}
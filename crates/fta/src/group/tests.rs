@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests {
+    use crate::group::{group_by_owner, group_by_package, summarize_groups};
+    use crate::structs::{FileData, ParseFailure};
+    use crate::test_utils::file_data;
+
+    fn packaged(
+        file_name: &str,
+        package: Option<&str>,
+        line_count: usize,
+        fta_score: f64,
+    ) -> FileData {
+        FileData {
+            package: package.map(|name| name.to_string()),
+            ..file_data(file_name, line_count, fta_score)
+        }
+    }
+
+    #[test]
+    fn test_group_by_package() {
+        let files = vec![
+            packaged("packages/a/x.ts", Some("a"), 10, 20.0),
+            packaged("packages/a/y.ts", Some("a"), 30, 60.0),
+            packaged("packages/a/z.ts", Some("a"), 10, 10.0),
+            packaged("packages/a/w.ts", Some("a"), 10, 5.0),
+            packaged("packages/b/x.ts", Some("b"), 10, 30.0),
+            file_data("scripts/build.ts", 10, 40.0),
+        ];
+
+        let groups = group_by_package(&files);
+        let names: Vec<&str> = groups.iter().map(|group| group.name.as_str()).collect();
+        assert_eq!(names, vec!["(no package)", "a", "b"]);
+
+        let a = &groups[1];
+        assert_eq!(a.file_count, 4);
+        assert_eq!(a.line_count, 60);
+        assert_eq!(a.average_fta_score, 23.75);
+        assert_eq!(a.weighted_fta_score, 2150.0 / 60.0);
+        assert_eq!(
+            a.worst_files,
+            vec!["packages/a/y.ts", "packages/a/x.ts", "packages/a/z.ts"]
+        );
+    }
+
+    #[test]
+    fn test_parse_failures_are_left_out_of_group_scores() {
+        let mut failed = packaged("packages/a/broken.ts", Some("a"), 100, 0.0);
+        failed.parse_failure = Some(ParseFailure {
+            message: "Unexpected token".to_string(),
+            line: 1,
+            column: 1,
+        });
+        let files = vec![failed, packaged("packages/a/x.ts", Some("a"), 10, 20.0)];
+
+        let groups = group_by_package(&files);
+        assert_eq!(groups[0].file_count, 1);
+        assert_eq!(groups[0].line_count, 10);
+        assert_eq!(groups[0].average_fta_score, 20.0);
+        assert_eq!(groups[0].worst_files, vec!["packages/a/x.ts"]);
+        assert_eq!(groups[0].parse_failures, 1);
+    }
+
+    #[test]
+    fn test_summarize_groups_with_several_groups_per_file() {
+        let files = vec![file_data("a.ts", 10, 20.0), file_data("b.ts", 10, 40.0)];
+
+        let groups = summarize_groups(
+            &files,
+            |data| {
+                if data.file_name == "a.ts" {
+                    vec!["x".to_string(), "y".to_string()]
+                } else {
+                    vec!["y".to_string()]
+                }
+            },
+            "(none)",
+        );
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "y");
+        assert_eq!(groups[0].file_count, 2);
+        assert_eq!(groups[1].name, "x");
+        assert_eq!(groups[1].file_count, 1);
+    }
+
+    #[test]
+    fn test_group_by_owner() {
+        let mut shared = file_data("shared.ts", 10, 50.0);
+        shared.owners = vec!["@org/web".to_string(), "@org/api".to_string()];
+        let mut web = file_data("web.ts", 10, 10.0);
+        web.owners = vec!["@org/web".to_string()];
+        let files = vec![shared, web, file_data("orphan.ts", 10, 20.0)];
+
+        let groups = group_by_owner(&files);
+        let names: Vec<&str> = groups.iter().map(|group| group.name.as_str()).collect();
+        assert_eq!(names, vec!["@org/api", "@org/web", "(unowned)"]);
+        assert_eq!(groups[1].file_count, 2);
+        assert_eq!(groups[1].worst_files, vec!["shared.ts", "web.ts"]);
+    }
+
+    #[test]
+    fn test_group_by_owner_counts_files_that_were_not_analyzed() {
+        let owned = |file_name: &str, owners: &[&str]| {
+            let mut data = file_data(file_name, 10, 30.0);
+            data.owners = owners.iter().map(|owner| owner.to_string()).collect();
+            data
+        };
+        let mut broken = owned("broken.ts", &["@org/web", "@org/api"]);
+        broken.line_count = 0;
+        broken.fta_score = 0.0;
+        broken.parse_failure = Some(ParseFailure {
+            message: "Unexpected token".to_string(),
+            line: 1,
+            column: 1,
+        });
+        let mut slow = owned("slow.ts", &["@org/web"]);
+        slow.line_count = 0;
+        slow.fta_score = 0.0;
+        slow.analysis_failure = Some("took longer than 10ms".to_string());
+        let mut large = owned("large.ts", &["@org/api"]);
+        large.line_count = 0;
+        large.fta_score = 0.0;
+        large.skipped = Some("too large".to_string());
+        let mut orphan = owned("orphan.min.ts", &[]);
+        orphan.skipped = Some("minified".to_string());
+        let files = vec![broken, slow, large, orphan, owned("web.ts", &["@org/web"])];
+
+        let groups = group_by_owner(&files);
+        let counts: Vec<(&str, usize, usize, usize, usize)> = groups
+            .iter()
+            .map(|group| {
+                (
+                    group.name.as_str(),
+                    group.file_count,
+                    group.parse_failures,
+                    group.analysis_failures,
+                    group.skipped,
+                )
+            })
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("@org/web", 1, 1, 1, 0),
+                ("(unowned)", 0, 0, 0, 1),
+                ("@org/api", 0, 1, 0, 1),
+            ]
+        );
+        assert_eq!(groups[0].line_count, 10);
+        assert_eq!(groups[0].average_fta_score, 30.0);
+        assert_eq!(groups[0].worst_files, vec!["web.ts"]);
+    }
+}
@@ -71,18 +71,12 @@ impl Visit for AstAnalyzer {
                 }
             },
             Expr::Array(array) => {
-                for elem in &array.elems {
-                    if let Some(element) = elem {
-                        match element {
-                            ExprOrSpread { expr, spread } => {
-                                if spread.is_some() {
-                                    self.unique_operators.insert("...".to_string());
-                                    self.total_operators += 1;
-                                }
-                                expr.visit_with(self);
-                            }
-                        }
+                for ExprOrSpread { expr, spread } in array.elems.iter().flatten() {
+                    if spread.is_some() {
+                        self.unique_operators.insert("...".to_string());
+                        self.total_operators += 1;
                     }
+                    expr.visit_with(self);
                 }
             }
             Expr::Arrow(arrow) => {
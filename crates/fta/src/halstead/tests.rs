@@ -16,8 +16,7 @@ mod tests {
     }
 
     fn analyze(module: &Module) -> HalsteadMetrics {
-        let metrics = analyze_module(module);
-        metrics
+        analyze_module(module)
     }
 
     #[test]
@@ -0,0 +1,82 @@
+use crate::analyze_tree;
+use crate::git::{GitError, GitRepository};
+use crate::structs::{FileData, FtaConfigResolved, HistoryFileScore, HistoryPoint};
+use log::debug;
+
+mod tests;
+
+/// Picks every `step`th commit, always keeping the most recent one so the series ends at `HEAD`.
+pub fn sample_commits<T: Clone>(commits: &[T], step: usize) -> Vec<T> {
+    let step = step.max(1);
+    let mut sampled: Vec<T> = commits.iter().step_by(step).cloned().collect();
+
+    if let Some(last) = commits.last() {
+        if !(commits.len() - 1).is_multiple_of(step) {
+            sampled.push(last.clone());
+        }
+    }
+
+    sampled
+}
+
+/// Summarizes the files of a commit. Files that failed to parse are only counted as such, and
+/// files that were skipped or failed to analyze are left out.
+pub fn summarize(commit: String, date: String, file_data_list: &[FileData]) -> HistoryPoint {
+    let (failed, analyzed): (Vec<&FileData>, Vec<&FileData>) = file_data_list
+        .iter()
+        .filter(|data| data.skipped.is_none() && data.analysis_failure.is_none())
+        .partition(|data| data.parse_failure.is_some());
+    let file_count = analyzed.len();
+    let line_count = analyzed.iter().map(|data| data.line_count).sum();
+    let total_score: f64 = analyzed.iter().map(|data| data.fta_score).sum();
+    let max_fta_score = analyzed
+        .iter()
+        .map(|data| data.fta_score)
+        .fold(0.0, f64::max);
+
+    HistoryPoint {
+        commit,
+        date,
+        file_count,
+        line_count,
+        average_fta_score: if file_count > 0 {
+            total_score / file_count as f64
+        } else {
+            0.0
+        },
+        max_fta_score,
+        parse_failures: failed.len(),
+        files: analyzed
+            .iter()
+            .map(|data| HistoryFileScore {
+                file_name: data.file_name.clone(),
+                line_count: data.line_count,
+                fta_score: data.fta_score,
+            })
+            .collect(),
+    }
+}
+
+pub fn analyze_history(
+    repo_path: &str,
+    since: &str,
+    step: usize,
+    config: &FtaConfigResolved,
+) -> Result<Vec<HistoryPoint>, GitError> {
+    let repository = GitRepository::open(repo_path)?;
+    let commits = repository.commits_since(since)?;
+    if commits.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut blobs = repository.blob_reader()?;
+    let mut history: Vec<HistoryPoint> = Vec::new();
+
+    for commit in sample_commits(&commits, step) {
+        debug!("Analyzing commit {} ({})", commit.id, commit.date);
+        let file_data_list = analyze_tree(&repository, &mut blobs, &commit.id, config, false)?;
+        history.push(summarize(commit.id, commit.date, &file_data_list));
+    }
+
+    Ok(history)
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::get_default_config;
+    use crate::history::{analyze_history, sample_commits, summarize};
+    use crate::test_utils::{file_data, git};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sample_commits_keeps_the_latest_commit() {
+        let commits = vec![1, 2, 3, 4, 5, 6];
+        assert_eq!(sample_commits(&commits, 1), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(sample_commits(&commits, 2), vec![1, 3, 5, 6]);
+        assert_eq!(sample_commits(&commits, 5), vec![1, 6]);
+        assert_eq!(sample_commits(&commits, 10), vec![1, 6]);
+        assert_eq!(sample_commits::<i32>(&[], 3), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let files = vec![file_data("a.ts", 10, 20.0), file_data("b.ts", 30, 40.0)];
+        let point = summarize("abc".to_string(), "2024-01-01".to_string(), &files);
+
+        assert_eq!(point.file_count, 2);
+        assert_eq!(point.line_count, 40);
+        assert_eq!(point.average_fta_score, 30.0);
+        assert_eq!(point.max_fta_score, 40.0);
+        assert_eq!(point.files.len(), 2);
+        assert_eq!(point.files[1].file_name, "b.ts");
+    }
+
+    #[test]
+    fn test_analyze_history_reads_past_commits() {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path();
+        git(repo, &["init", "--quiet"]);
+
+        let first = r#"
+            function add(a: number, b: number): number {
+                return a + b;
+            }
+            const one = add(1, 2);
+            const two = add(3, 4);
+            const three = add(one, two);
+            console.log(one, two, three);
+        "#;
+        fs::write(repo.join("a.ts"), first).unwrap();
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "--quiet", "-m", "first"]);
+
+        fs::create_dir(repo.join("src")).unwrap();
+        fs::write(repo.join("src/b.ts"), first).unwrap();
+        fs::write(repo.join("notes.md"), "not analyzed").unwrap();
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "--quiet", "-m", "second"]);
+
+        // The working tree should be irrelevant to the analysis
+        fs::remove_file(repo.join("a.ts")).unwrap();
+
+        let history =
+            analyze_history(repo.to_str().unwrap(), "HEAD~1", 1, &get_default_config()).unwrap();
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].file_count, 1);
+        assert_eq!(history[0].files[0].file_name, "a.ts");
+        assert_eq!(history[1].file_count, 2);
+        assert_eq!(history[1].files[1].file_name, "src/b.ts");
+        assert_eq!(history[0].files[0].fta_score, history[1].files[1].fta_score);
+    }
+
+    #[test]
+    fn test_analyze_history_outside_a_repository() {
+        let dir = TempDir::new().unwrap();
+        let result = analyze_history(
+            dir.path().to_str().unwrap(),
+            "HEAD",
+            1,
+            &get_default_config(),
+        );
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use crate::config::{get_default_config, merge_config, ConfigError};
+use crate::structs::{FtaConfigOptional, FtaConfigOverride};
+use crate::utils::{exclude_directories_matcher, get_file_name, is_excluded_directory};
+use crate::{analyze, build_walk};
+
+mod tests;
+
+/// Extensions `fta init` looks for, in the order they're listed in the generated config
+const SOURCE_EXTENSIONS: [&str; 11] = [
+    ".js", ".jsx", ".ts", ".tsx", ".mjs", ".cjs", ".mts", ".cts", ".vue", ".svelte", ".astro",
+];
+
+/// Names of directories that usually hold generated or vendored code
+const GENERATED_DIRECTORIES: [&str; 12] = [
+    "vendor",
+    "vendors",
+    "third_party",
+    "generated",
+    "__generated__",
+    "out",
+    "coverage",
+    "storybook-static",
+    ".next",
+    ".nuxt",
+    ".svelte-kit",
+    "node_modules",
+];
+
+/// The percentile of the current scores `fta init` sets `score_cap` at by default
+pub const DEFAULT_PERCENTILE: u8 = 95;
+
+/// Builds a config tuned to the project at `project`: the extensions it uses beyond the default
+/// ones, exclusions for the generated and vendored directories it has, and a `score_cap` at the
+/// given percentile of the current scores. Files scoring above it get an override capping them at
+/// their current score, so that the gate passes today and only fails when code gets worse. Fails
+/// when a config file in the project is invalid.
+pub fn scaffold_config(project: &str, percentile: u8) -> Result<FtaConfigOptional, ConfigError> {
+    let mut scaffold = FtaConfigOptional::default();
+    let default_config = get_default_config();
+
+    let (extensions, exclude_directories) =
+        survey_project(project, &default_config.exclude_directories);
+    // Extensions are added to the defaults, so that files of another kind added later are analyzed
+    let extensions: Vec<String> = extensions
+        .into_iter()
+        .filter(|extension| !default_config.extensions.contains(extension))
+        .collect();
+    if !extensions.is_empty() {
+        scaffold.extensions = Some(extensions);
+    }
+    let exclude_directories: Vec<String> = exclude_directories
+        .into_iter()
+        .filter(|directory| !default_config.exclude_directories.contains(directory))
+        .collect();
+    if !exclude_directories.is_empty() {
+        scaffold.exclude_directories = Some(exclude_directories);
+    }
+
+    // Analyze with the suggested exclusions, without any gate getting in the way
+    let mut config = merge_config(&default_config, scaffold.clone());
+    config.score_cap = usize::MAX;
+    config.command_line.score_cap = Some(usize::MAX);
+    let mut findings = analyze(project, &config)?;
+    findings.retain(|file_data| file_data.is_analyzed());
+    if findings.is_empty() {
+        return Ok(scaffold);
+    }
+    findings.sort_unstable_by(|a, b| a.fta_score.total_cmp(&b.fta_score));
+
+    let percentile = percentile.clamp(1, 100) as usize;
+    let index = (findings.len() * percentile).div_ceil(100).max(1) - 1;
+    let score_cap = findings[index].fta_score.ceil() as usize;
+    scaffold.score_cap = Some(score_cap);
+
+    let overrides: Vec<FtaConfigOverride> = findings
+        .iter()
+        .rev()
+        .take_while(|file_data| file_data.fta_score > score_cap as f64)
+        .map(|file_data| FtaConfigOverride {
+            files: Some(vec![format!(
+                "/{}",
+                globset::escape(file_data.file_name.trim_start_matches('/'))
+            )]),
+            owners: None,
+            config: FtaConfigOptional {
+                score_cap: Some(file_data.fta_score.ceil() as usize),
+                ..Default::default()
+            },
+        })
+        .collect();
+    if !overrides.is_empty() {
+        scaffold.overrides = Some(overrides);
+    }
+
+    Ok(scaffold)
+}
+
+/// Walks the project, returning the source extensions it uses and the directories that look
+/// generated or vendored, as patterns anchored to the project root. Files in directories that are
+/// already excluded are left out.
+fn survey_project(project: &str, excluded: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut found_extensions: Vec<&str> = Vec::new();
+    let mut generated_directories: Vec<String> = Vec::new();
+    let excluded = exclude_directories_matcher(excluded);
+
+    for entry in build_walk(project).flatten() {
+        let relative_path = get_file_name(entry.path(), project).replace('\\', "/");
+        let relative_path = relative_path.trim_start_matches('/');
+        let is_dir = entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_dir());
+
+        let in_generated_directory = generated_directories
+            .iter()
+            .any(|directory| relative_path.starts_with(&format!("{}/", &directory[1..])));
+        if in_generated_directory {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy();
+        if is_dir && entry.depth() > 0 && GENERATED_DIRECTORIES.contains(&name.as_ref()) {
+            generated_directories.push(format!("/{}", relative_path));
+        } else if !is_dir && !is_excluded_directory(relative_path, &excluded) {
+            if let Some(extension) = SOURCE_EXTENSIONS
+                .iter()
+                .find(|extension| name.ends_with(*extension))
+            {
+                if !found_extensions.contains(extension) {
+                    found_extensions.push(extension);
+                }
+            }
+        }
+    }
+
+    let extensions = SOURCE_EXTENSIONS
+        .iter()
+        .filter(|extension| found_extensions.contains(extension))
+        .map(|extension| extension.to_string())
+        .collect();
+
+    (extensions, generated_directories)
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use crate::analyze;
+    use crate::config::{get_default_config, merge_config};
+    use crate::init::scaffold_config;
+    use crate::test_utils::write;
+    use tempfile::TempDir;
+
+    const SIMPLE: &str = r#"
+        const one = 1;
+        const two = 2;
+        const three = 3;
+        const four = 4;
+        const five = 5;
+        const six = 6;
+        const seven = 7;
+    "#;
+
+    const COMPLEX: &str = r#"
+        export function classify(value: number, label: string): string {
+            if (value > 100 && label.length > 3) {
+                return label.toUpperCase();
+            } else if (value > 50 || label === "mid") {
+                return label.toLowerCase();
+            }
+            for (let i = 0; i < value; i++) {
+                if (i % 3 === 0) {
+                    label += i;
+                } else if (i % 5 === 0) {
+                    label = label.slice(1);
+                }
+            }
+            switch (label) {
+                case "a": return "first";
+                case "b": return "second";
+                default: return value > 10 ? label : "small";
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_scaffold_config() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        for index in 0..4 {
+            write(root, &format!("src/simple{}.ts", index), SIMPLE);
+        }
+        write(root, "src/[id]/complex.mts", COMPLEX);
+        write(
+            root,
+            "src/Button.vue",
+            "<script setup lang=\"ts\">\nconst label = 'OK';\n</script>\n",
+        );
+        write(root, "src/vendor/lib.js", COMPLEX);
+        write(root, "src/vendor/nested/generated/lib.js", COMPLEX);
+        write(root, "generated/api.ts", COMPLEX);
+        write(root, "dist/index.js", COMPLEX);
+        let project = root.to_str().unwrap();
+
+        let scaffold = scaffold_config(project, 80).unwrap();
+
+        assert_eq!(scaffold.replace, None);
+        assert_eq!(scaffold.extensions, Some(vec![".vue".to_string()]));
+        let mut exclude_directories = scaffold.exclude_directories.clone().unwrap();
+        exclude_directories.sort();
+        assert_eq!(exclude_directories, vec!["/generated", "/src/vendor"]);
+
+        let overrides = scaffold.overrides.clone().unwrap();
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(
+            overrides[0].files,
+            Some(vec!["/src/[[]id[]]/complex.mts".to_string()])
+        );
+        assert!(overrides[0].config.score_cap > scaffold.score_cap);
+
+        // The gate passes with the scaffolded config
+        let config = merge_config(&get_default_config(), scaffold);
+        assert_eq!(analyze(project, &config).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_scaffold_config_ignores_nested_score_caps() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "src/simple.ts", SIMPLE);
+        write(root, "packages/a/complex.ts", COMPLEX);
+        write(
+            root,
+            "packages/a/fta.json",
+            r#"{ "score_cap": 1, "overrides": [{ "files": ["*.ts"], "score_cap": 2 }] }"#,
+        );
+
+        let scaffold = scaffold_config(root.to_str().unwrap(), 50).unwrap();
+
+        let overrides = scaffold.overrides.unwrap();
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(
+            overrides[0].files,
+            Some(vec!["/packages/a/complex.ts".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_scaffold_config_of_an_empty_project() {
+        let dir = TempDir::new().unwrap();
+
+        let scaffold = scaffold_config(dir.path().to_str().unwrap(), 95).unwrap();
+
+        assert_eq!(scaffold.extensions, None);
+        assert_eq!(scaffold.score_cap, None);
+        assert_eq!(scaffold.overrides.map(|o| o.len()), None);
+    }
+}
@@ -0,0 +1,80 @@
+use std::any::Any;
+use std::fmt;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+mod tests;
+
+/// Stack of the threads files are analyzed on, so that deeply nested code doesn't overflow it
+const STACK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Why a task didn't complete
+#[derive(Debug)]
+pub enum Interrupted {
+    Panicked(String),
+    /// The task ran longer than its time budget. Threads can't be stopped, so it's left to finish in
+    /// the background.
+    TimedOut(Duration),
+    /// The thread to run the task on couldn't be started
+    Unavailable(io::Error),
+}
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Interrupted::Panicked(message) => write!(f, "panicked: {}", message),
+            Interrupted::TimedOut(budget) => {
+                write!(f, "took longer than {}ms", budget.as_millis())
+            }
+            Interrupted::Unavailable(err) => write!(f, "unable to start a thread: {}", err),
+        }
+    }
+}
+
+/// Runs a task on the current thread, catching a panic so that it only interrupts that task
+pub fn catch<T, F>(task: F) -> Result<T, Interrupted>
+where
+    F: FnOnce() -> T,
+{
+    panic::catch_unwind(AssertUnwindSafe(task))
+        .map_err(|payload| Interrupted::Panicked(panic_message(payload)))
+}
+
+/// Runs a task on its own thread, named `name`, so that a panic only interrupts that task, and stops
+/// waiting for it once `budget` is spent
+pub fn run<T, F>(name: &str, budget: Duration, task: F) -> Result<T, Interrupted>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    let handle = thread::Builder::new()
+        .name(name.to_string())
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            let _ = sender.send(task());
+        })
+        .map_err(Interrupted::Unavailable)?;
+
+    match receiver.recv_timeout(budget) {
+        Ok(value) => Ok(value),
+        Err(RecvTimeoutError::Timeout) => Err(Interrupted::TimedOut(budget)),
+        Err(RecvTimeoutError::Disconnected) => match handle.join() {
+            Err(payload) => Err(Interrupted::Panicked(panic_message(payload))),
+            Ok(()) => Err(Interrupted::Panicked("no result".to_string())),
+        },
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message.to_string();
+    }
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(_) => "unknown cause".to_string(),
+    }
+}
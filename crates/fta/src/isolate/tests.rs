@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use crate::isolate::{catch, run, Interrupted};
+    use std::thread;
+    use std::time::Duration;
+
+    const BUDGET: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn test_run_returns_the_result() {
+        assert_eq!(catch(|| 1 + 1).unwrap(), 2);
+        assert_eq!(run("a.ts", BUDGET, || "done").unwrap(), "done");
+    }
+
+    #[test]
+    fn test_run_catches_panics() {
+        let error = catch(|| -> usize { panic!("at the disco") }).unwrap_err();
+        assert_eq!(error.to_string(), "panicked: at the disco");
+
+        let value = 7;
+        let error = run("a.ts", BUDGET, move || -> usize {
+            panic!("{} failed", value)
+        })
+        .unwrap_err();
+        assert_eq!(error.to_string(), "panicked: 7 failed");
+    }
+
+    #[test]
+    fn test_run_stops_waiting_after_the_budget() {
+        let error = run("a.ts", Duration::from_millis(10), || {
+            thread::sleep(Duration::from_secs(1));
+        })
+        .unwrap_err();
+
+        assert!(matches!(error, Interrupted::TimedOut(_)));
+        assert_eq!(error.to_string(), "took longer than 10ms");
+    }
+
+    #[test]
+    fn test_run_has_room_for_deep_recursion() {
+        fn depth(n: usize) -> usize {
+            let padding = [n; 16];
+            if n == 0 {
+                0
+            } else {
+                1 + depth(n - 1) + padding[0] - n
+            }
+        }
+
+        assert_eq!(run("a.ts", BUDGET, || depth(200_000)).unwrap(), 200_000);
+    }
+}
@@ -1,25 +1,65 @@
+#![allow(clippy::module_inception)]
+
+mod codeowners;
 pub mod config;
 mod cyclo;
+mod generated;
+mod git;
+pub mod group;
 mod halstead;
+mod history;
+pub mod init;
+mod isolate;
+pub mod lines;
 pub mod parse;
+mod read;
+mod sfc;
 mod structs;
+mod suppress;
+mod tree;
+mod tsconfig;
 mod utils;
 mod walk;
+mod workspace;
 
 #[cfg(feature = "use_output")]
 pub mod output;
 
+#[cfg(test)]
+mod lib_tests;
+#[cfg(test)]
+mod test_utils;
+
+use codeowners::{CodeOwners, CodeOwnersCache};
+use config::{apply_overrides, ConfigError};
+use git::{BlobReader, GitError, GitRepository};
+use globset::GlobBuilder;
 use ignore::DirEntry;
-use ignore::WalkBuilder;
+use ignore::{Walk, WalkBuilder};
 use log::debug;
 use log::warn;
+use parse::Dialect;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
-use structs::{FileData, FtaConfigResolved, HalsteadMetrics};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+use structs::{
+    FileData, FtaConfigResolved, HalsteadMetrics, HistoryPoint, Language, LineCounts, ParseFailure,
+    ScoreLines, SourceType, SuppressionKind,
+};
 use swc_ecma_ast::Module;
-use swc_ecma_parser::error::Error;
-use utils::{check_score_cap_breach, get_assessment, is_valid_file, warn_about_language};
+use tree::Tree;
+use tsconfig::TsConfigs;
+use utils::{
+    check_score_cap_breach, get_assessment, get_file_name, is_valid_file, is_valid_path,
+    split_glob, warn_about_language,
+};
 use walk::walk_and_analyze_files;
+use workspace::{Package, Workspace};
 
 pub fn analyze_file(module: &Module, line_count: usize) -> (usize, HalsteadMetrics, f64) {
     let cyclo = cyclo::cyclomatic_complexity(module);
@@ -50,8 +90,8 @@ pub fn analyze_file(module: &Module, line_count: usize) -> (usize, HalsteadMetri
     (cyclo, halstead_metrics, fta_score)
 }
 
-fn analyze_parsed_code(file_name: String, module: Module, line_count: usize) -> FileData {
-    let (cyclo, halstead, fta_score) = analyze_file(&module, line_count);
+fn analyze_parsed_code(file_name: String, module: &Module, line_count: usize) -> FileData {
+    let (cyclo, halstead, fta_score) = analyze_file(module, line_count);
     debug!("{} cyclo: {}, halstead: {:?}", file_name, cyclo, halstead);
 
     FileData {
@@ -60,105 +100,358 @@ fn analyze_parsed_code(file_name: String, module: Module, line_count: usize) ->
         halstead,
         fta_score,
         line_count,
+        lines: LineCounts::default(),
         assessment: get_assessment(fta_score),
+        package: None,
+        owners: vec![],
+        suppressions: vec![],
+        gate_fta_score: None,
+        syntax: Language::TypeScript,
+        source_type: SourceType::Module,
+        parse_failure: None,
+        skipped: None,
+        analysis_failure: None,
     }
 }
 
-fn collect_results(
-    entry: &DirEntry,
-    repo_path: &str,
-    module: Module,
-    line_count: usize,
-    score_cap: usize,
+/// A file that couldn't be parsed, reported without metrics
+fn failed_file_data(
+    file_name: String,
+    source_code: &str,
+    dialect: Dialect,
+    failure: ParseFailure,
 ) -> FileData {
-    // Parse the source code and run the analysis
-    let file_name = entry
-        .path()
-        .strip_prefix(repo_path)
-        .unwrap()
-        .display()
-        .to_string();
-    let file_name_cloned = file_name.clone();
-    let file_data = analyze_parsed_code(file_name, module, line_count);
+    let physical = source_code.lines().count();
+    let blank = source_code
+        .lines()
+        .filter(|line| line.trim().is_empty())
+        .count();
+
+    FileData {
+        file_name,
+        cyclo: 0,
+        halstead: HalsteadMetrics::default(),
+        fta_score: 0.0,
+        line_count: physical - blank,
+        lines: LineCounts {
+            physical,
+            blank,
+            ..Default::default()
+        },
+        assessment: "Failed to parse".to_string(),
+        package: None,
+        owners: vec![],
+        suppressions: vec![],
+        gate_fta_score: None,
+        syntax: dialect.language,
+        source_type: dialect.source_type.unwrap_or(SourceType::Module),
+        parse_failure: Some(failure),
+        skipped: None,
+        analysis_failure: None,
+    }
+}
 
-    // Keep a record of the fta_score before moving the FileData
-    let fta_score = file_data.fta_score;
+/// A file that wasn't analyzed, e.g. because it couldn't be read
+fn skipped_file_data(file_name: String, reason: String, config: &FtaConfigResolved) -> FileData {
+    let dialect = parse::dialect_for(&file_name, &config.parser);
 
-    // Check if the score cap is breached
-    check_score_cap_breach(file_name_cloned.clone(), fta_score, score_cap);
+    FileData {
+        file_name,
+        cyclo: 0,
+        halstead: HalsteadMetrics::default(),
+        fta_score: 0.0,
+        line_count: 0,
+        lines: LineCounts::default(),
+        assessment: "Skipped".to_string(),
+        package: None,
+        owners: vec![],
+        suppressions: vec![],
+        gate_fta_score: None,
+        syntax: dialect.language,
+        source_type: dialect.source_type.unwrap_or(SourceType::Module),
+        parse_failure: None,
+        skipped: Some(reason),
+        analysis_failure: None,
+    }
+}
 
-    file_data
+/// A file whose analysis was cut short, see `analyze_isolated`
+fn interrupted_file_data(
+    file_name: String,
+    reason: String,
+    config: &FtaConfigResolved,
+) -> FileData {
+    FileData {
+        assessment: "Failed to analyze".to_string(),
+        skipped: None,
+        analysis_failure: Some(reason),
+        ..skipped_file_data(file_name, String::new(), config)
+    }
 }
 
 fn do_analysis(
-    entry: &DirEntry,
-    repo_path: &str,
+    file_name: &str,
     config: &FtaConfigResolved,
     source_code: &str,
-    use_tsx: bool,
-) -> Result<FileData, Error> {
-    let (result, line_count) = parse::parse_module(source_code, use_tsx, config.include_comments);
+    dialect: Dialect,
+) -> Result<FileData, ParseFailure> {
+    let parsed = parse::parse_source(
+        file_name,
+        source_code,
+        dialect,
+        config.include_comments,
+        &config.parser,
+    );
+    let suppressions = suppress::describe(&parsed.directives);
+    let module = match &parsed.module {
+        Ok(module) => module,
+        Err(err) => return Err(parsed.failure(err)),
+    };
+
+    for suppression in &suppressions {
+        for rule in &suppression.rules {
+            if !suppress::GATE_RULES.contains(&rule.as_str()) {
+                warn!(
+                    "Unknown rule {} in fta-disable on line {} of {}, expected one of: {}",
+                    rule,
+                    suppression.line,
+                    file_name,
+                    suppress::GATE_RULES.join(", ")
+                );
+            }
+        }
+    }
+
+    // Logical lines ignore the layout, and so should the rest of the score
+    let unwrapped;
+    let module = if config.score_lines == ScoreLines::Logical {
+        let mut module = module.clone();
+        lines::remove_parens(&mut module);
+        unwrapped = module;
+        &unwrapped
+    } else {
+        module
+    };
+
+    // Gates use the score of the file without the suppressed lines and functions
+    let gate_fta_score = if suppressions
+        .iter()
+        .any(|suppression| suppression.kind != SuppressionKind::File)
+    {
+        let mut stripped = module.clone();
+        let removed_lines =
+            suppress::strip_suppressed(&mut stripped, &parsed.directives, &parsed.source_map);
+        let line_count = match config.score_lines {
+            ScoreLines::Source => {
+                let removed_code_lines = removed_lines
+                    .iter()
+                    .filter(|line| {
+                        !parsed.blank_lines.contains(line) && !parsed.comment_lines.contains(line)
+                    })
+                    .count();
+                parsed.line_count.saturating_sub(removed_code_lines)
+            }
+            ScoreLines::Physical => parsed.lines.physical.saturating_sub(removed_lines.len()),
+            ScoreLines::Logical => lines::logical_lines(&stripped),
+        };
+        let (_, _, fta_score) = analyze_file(&stripped, line_count);
+        Some(fta_score)
+    } else {
+        None
+    };
+
+    let line_count = match config.score_lines {
+        ScoreLines::Source => parsed.line_count,
+        ScoreLines::Physical => parsed.lines.physical,
+        ScoreLines::Logical => parsed.lines.logical,
+    };
+    let mut file_data = analyze_parsed_code(file_name.to_string(), module, line_count);
+    file_data.lines = parsed.lines.clone();
+    file_data.suppressions = suppressions;
+    file_data.gate_fta_score = gate_fta_score;
+    file_data.syntax = dialect.language;
+    file_data.source_type = parsed.source_type;
+
+    Ok(file_data)
+}
+
+/// Parses and analyzes in-memory source code, retrying with the opposite JSX setting
+/// if the file extension turns out to be misleading. Only the scripts of Vue, Svelte and Astro
+/// components are analyzed, keeping their line numbers in the component. Files that can't be
+/// parsed either way are reported with the error of the syntax their extension calls for. Files
+/// that look generated or minified are skipped, unless `include_generated` is set.
+fn analyze_source(file_name: &str, source_code: &str, config: &FtaConfigResolved) -> FileData {
+    let source_code = source_code.strip_prefix('\u{FEFF}').unwrap_or(source_code);
+    if !config.include_generated {
+        if let Some(reason) = generated::detect(file_name, source_code) {
+            debug!("Skipping {}: {}", file_name, reason);
+            return skipped_file_data(file_name.to_string(), reason, config);
+        }
+    }
+    let extracted = sfc::extract_script(file_name, source_code);
+    let (source_code, dialect) = match &extracted {
+        Some(extracted) => (extracted.source.as_str(), extracted.dialect),
+        None => (source_code, parse::dialect_for(file_name, &config.parser)),
+    };
+
+    let failure = match do_analysis(file_name, config, source_code, dialect) {
+        Ok(data) => return data,
+        Err(failure) => failure,
+    };
+
+    let use_tsx = dialect.language.is_jsx();
+    warn_about_language(file_name, use_tsx);
+    let retry_dialect = Dialect {
+        language: dialect.language.with_jsx(!use_tsx),
+        ..dialect
+    };
+    if let Ok(data) = do_analysis(file_name, config, source_code, retry_dialect) {
+        return data;
+    }
+
+    warn!(
+        "Failed to parse {} at {}:{}: {}",
+        file_name, failure.line, failure.column, failure.message
+    );
+    failed_file_data(file_name.to_string(), source_code, dialect, failure)
+}
+
+/// Analyzes a source so that a panic only fails that file rather than the whole run. With
+/// `file_timeout_ms`, the source is analyzed on its own thread so that a file taking longer also
+/// only fails that file.
+fn analyze_isolated(
+    file_name: &str,
+    source_code: &str,
+    config: &Arc<FtaConfigResolved>,
+) -> FileData {
+    let result = match config.file_timeout_ms {
+        None => isolate::catch(|| analyze_source(file_name, source_code, config)),
+        Some(budget) => {
+            let (name, source, task_config) = (
+                file_name.to_string(),
+                source_code.to_string(),
+                Arc::clone(config),
+            );
+            isolate::run(file_name, Duration::from_millis(budget), move || {
+                analyze_source(&name, &source, &task_config)
+            })
+        }
+    };
 
     match result {
-        Ok(module) => Ok(collect_results(
-            entry,
-            repo_path,
-            module,
-            line_count,
-            config.score_cap,
-        )),
-        Err(err) => Err(err),
+        Ok(file_data) => file_data,
+        Err(err) => {
+            warn!("Failed to analyze {}: {}", file_name, err);
+            interrupted_file_data(file_name.to_string(), err.to_string(), config)
+        }
     }
 }
 
+fn collect_results(
+    file_name: String,
+    source_code: &str,
+    config: &Arc<FtaConfigResolved>,
+) -> Option<Vec<FileData>> {
+    let file_data = analyze_isolated(&file_name, source_code, config);
+
+    // Check if the score cap is breached, unless the file is suppressed
+    if let Some(score) = suppress::gate_score(&file_data, "score-cap") {
+        check_score_cap_breach(file_name, score, config.score_cap);
+    }
+
+    let mut file_data_list: Vec<FileData> = Vec::new();
+
+    // Only include files that are equal to or greater than the `exclude_under` option. Files that
+    // weren't analyzed in full are always included, so that they aren't missed.
+    if file_data.line_count > config.exclude_under || !file_data.is_analyzed() {
+        file_data_list.push(file_data);
+    }
+
+    Some(file_data_list)
+}
+
 fn process_entry(
     entry: DirEntry,
-    repo_path: &String,
-    config: &FtaConfigResolved,
+    repo_path: &str,
+    config: &Arc<FtaConfigResolved>,
 ) -> Option<Vec<FileData>> {
-    let file_name = entry.path().display();
-    let source_code = match fs::read_to_string(file_name.to_string()) {
+    let file_name = get_file_name(entry.path(), repo_path);
+    let source_code = match read::read_source(entry.path(), config) {
         Ok(code) => code,
-        Err(_) => return None,
+        Err(err) => {
+            warn!("Skipping {}: {}", file_name, err);
+            return Some(vec![skipped_file_data(file_name, err.to_string(), config)]);
+        }
     };
 
-    let file_extension = entry
-        .path()
-        .extension()
-        .and_then(std::ffi::OsStr::to_str)
-        .unwrap_or_default()
-        .to_string();
-    let use_tsx = file_extension == "tsx" || file_extension == "jsx";
+    collect_results(file_name, &source_code, config)
+}
 
-    let mut file_data_result = do_analysis(&entry, repo_path, &config, &source_code, use_tsx);
+/// Analyzes the files of the tree of `rev`, skipping those excluded by its .ftaignore files and
+/// configuring each file from its config files. With `enforce_score_cap`, exits as soon as a file
+/// breaches the score cap of its config.
+fn analyze_tree(
+    repository: &GitRepository,
+    blobs: &mut BlobReader,
+    rev: &str,
+    config: &FtaConfigResolved,
+    enforce_score_cap: bool,
+) -> Result<Vec<FileData>, GitError> {
+    let entries = repository.list_files(rev)?;
+    let mut tree = Tree::new(&entries, rev, Path::new(repository.path()), config, blobs)?;
+    let base_config = Arc::new(config.clone());
+    let mut file_data_list: Vec<FileData> = Vec::new();
 
-    if file_data_result.is_err() {
-        warn_about_language(&file_name.to_string(), use_tsx);
-        file_data_result = do_analysis(&entry, repo_path, &config, &source_code, !use_tsx);
-    }
+    for entry in &entries {
+        if tree.is_ignored(&entry.path) {
+            continue;
+        }
+        let base = tree
+            .config_for(&entry.path, blobs)?
+            .unwrap_or_else(|| Arc::clone(&base_config));
+        let config = match apply_overrides(&base, &entry.path, &[]) {
+            Some(file_config) => Arc::new(file_config),
+            None => base,
+        };
 
-    if file_data_result.is_err() {
-        warn!(
-            "Failed to analyze {}: {:?}",
-            file_name,
-            file_data_result.unwrap_err()
-        );
-        return None;
-    }
+        let file_name = entry.path.rsplit('/').next().unwrap_or_default();
+        if !is_valid_path(file_name, &entry.path, &config) {
+            continue;
+        }
 
-    let mut file_data_list: Vec<FileData> = Vec::new();
+        let (size, bytes) = blobs.read(&entry.object_id, config.max_file_size as u64)?;
+        let source_code = match read::check_size(size, &config)
+            .and_then(|_| read::decode(bytes.unwrap_or_default(), config.encoding))
+        {
+            Ok(code) => code,
+            Err(err) => {
+                warn!("Skipping {} at {}: {}", entry.path, rev, err);
+                file_data_list.push(skipped_file_data(
+                    entry.path.clone(),
+                    err.to_string(),
+                    &config,
+                ));
+                continue;
+            }
+        };
 
-    // Only include files that are equal to or greater than the `exclude_under` option
-    match file_data_result {
-        Ok(data) if data.line_count > config.exclude_under => file_data_list.push(data),
-        _ => {}
+        let data = analyze_isolated(&entry.path, &source_code, &config);
+        if enforce_score_cap {
+            if let Some(score) = suppress::gate_score(&data, "score-cap") {
+                check_score_cap_breach(entry.path.clone(), score, config.score_cap);
+            }
+        }
+        if data.line_count > config.exclude_under || !data.is_analyzed() {
+            file_data_list.push(data);
+        }
     }
 
-    Some(file_data_list)
+    Ok(file_data_list)
 }
 
-pub fn analyze(repo_path: &String, config: &FtaConfigResolved) -> Vec<FileData> {
-    // Initialize the logger
+/// Sets up logging to stderr. Every entry point below does so itself, the CLI also calls it before
+/// resolving its config so that warnings about the config aren't lost.
+pub fn init_logger() {
     let mut builder = env_logger::Builder::new();
 
     // Check if debug mode is enabled using an environment variable
@@ -167,13 +460,372 @@ pub fn analyze(repo_path: &String, config: &FtaConfigResolved) -> Vec<FileData>
     } else {
         builder.filter_level(log::LevelFilter::Info);
     }
-    builder.init();
+    // Several entry points may run in the same process, only the first one sets the logger up
+    let _ = builder.try_init();
+}
+
+/// Name of the gitignore-style files excluding paths from analysis, honoured in every directory
+pub(crate) const FTA_IGNORE_FILENAME: &str = ".ftaignore";
 
-    let walk = WalkBuilder::new(repo_path)
+fn build_walk(path: &str) -> Walk {
+    WalkBuilder::new(path)
         .git_ignore(true)
         .git_exclude(true)
         .standard_filters(true)
-        .build();
+        .add_custom_ignore_filename(FTA_IGNORE_FILENAME)
+        .build()
+}
+
+pub fn analyze(repo_path: &str, config: &FtaConfigResolved) -> Result<Vec<FileData>, ConfigError> {
+    init_logger();
+
+    let walk = build_walk(repo_path);
+    let mut context = WalkContext::new(repo_path, config);
+
+    analyze_walk(walk, repo_path, &mut context)
+}
+
+/// What the walks of a run share, so that it's only read once: the workspace of each walked root,
+/// the CODEOWNERS of each repository and the tsconfig.json files. File names are reported relative to
+/// `name_root`, which is also where CODEOWNERS is looked up.
+struct WalkContext<'a> {
+    name_root: &'a str,
+    config: &'a FtaConfigResolved,
+    /// A copy of `config` that the files it applies to share with the threads analyzing them
+    shared_config: Arc<FtaConfigResolved>,
+    workspaces: HashMap<String, Rc<Workspace<'a>>>,
+    code_owners: CodeOwnersCache,
+    tsconfigs: TsConfigs,
+}
+
+impl<'a> WalkContext<'a> {
+    fn new(name_root: &'a str, config: &'a FtaConfigResolved) -> WalkContext<'a> {
+        WalkContext {
+            name_root,
+            config,
+            shared_config: Arc::new(config.clone()),
+            workspaces: HashMap::new(),
+            code_owners: CodeOwnersCache::new(),
+            tsconfigs: TsConfigs::new(),
+        }
+    }
+
+    /// The workspace rooted at `repo_path`, read the first time a walk starts there
+    fn workspace(&mut self, repo_path: &str) -> Rc<Workspace<'a>> {
+        let (name_root, config) = (self.name_root, self.config);
+        self.workspaces
+            .entry(repo_path.to_string())
+            .or_insert_with(|| Rc::new(Workspace::new(repo_path, name_root, config)))
+            .clone()
+    }
+}
+
+/// The package and owners of a file, along with any config specific to it
+struct FileContext {
+    package: Option<Rc<Package>>,
+    owners: Vec<String>,
+    config: Option<Arc<FtaConfigResolved>>,
+}
+
+impl FileContext {
+    fn new(
+        path: &Path,
+        name_root: &str,
+        workspace: &Workspace,
+        code_owners: Option<&CodeOwners>,
+        tsconfigs: &TsConfigs,
+        config: &FtaConfigResolved,
+    ) -> Result<FileContext, ConfigError> {
+        let file_name = get_file_name(path, name_root);
+        let package = workspace.package_for(path);
+        let owners = code_owners
+            .map(|code_owners| code_owners.owners_for(&file_name))
+            .unwrap_or_default();
+        let directory_config = workspace.config_for(path)?;
+        let file_config = match apply_overrides(
+            directory_config.as_deref().unwrap_or(config),
+            &file_name,
+            &owners,
+        ) {
+            Some(config) => Some(Arc::new(config)),
+            None => directory_config,
+        };
+        let config = with_tsconfig(file_config, config, tsconfigs, path);
+
+        Ok(FileContext {
+            package,
+            owners,
+            config,
+        })
+    }
+
+    fn config<'a>(&'a self, base: &'a Arc<FtaConfigResolved>) -> &'a Arc<FtaConfigResolved> {
+        self.config.as_ref().unwrap_or(base)
+    }
+}
+
+/// Fills the parser options the config of a file leaves unset from its tsconfig.json, if any
+fn with_tsconfig(
+    file_config: Option<Arc<FtaConfigResolved>>,
+    base: &FtaConfigResolved,
+    tsconfigs: &TsConfigs,
+    path: &Path,
+) -> Option<Arc<FtaConfigResolved>> {
+    let config = file_config.as_deref().unwrap_or(base);
+    let parser = match tsconfigs.parser_options_for(path) {
+        Some(options) => config.parser.clone().or((*options).clone()),
+        None => return file_config,
+    };
+    if parser == config.parser {
+        return file_config;
+    }
+
+    let mut config = config.clone();
+    config.parser = parser;
+    Some(Arc::new(config))
+}
+
+/// Analyzes the walked files, tagging each with its workspace package and CODEOWNERS owners. The
+/// config of each file is resolved before it's validated and analyzed, from the config files of its
+/// directories and the overrides matching it. An invalid config file stops the analysis.
+fn analyze_walk<I>(
+    walk: I,
+    repo_path: &str,
+    context: &mut WalkContext,
+) -> Result<Vec<FileData>, ConfigError>
+where
+    I: Iterator<Item = Result<DirEntry, ignore::Error>>,
+{
+    let workspace = context.workspace(repo_path);
+    let WalkContext {
+        name_root,
+        config,
+        ref shared_config,
+        ref code_owners,
+        ref tsconfigs,
+        ..
+    } = *context;
+    let code_owners = code_owners.for_root(name_root);
+    let config_error: RefCell<Option<ConfigError>> = RefCell::new(None);
+    let file_context = |path: &Path, config: &FtaConfigResolved| {
+        if config_error.borrow().is_some() {
+            return None;
+        }
+        match FileContext::new(
+            path,
+            name_root,
+            &workspace,
+            code_owners.as_deref(),
+            tsconfigs,
+            config,
+        ) {
+            Ok(context) => Some(context),
+            Err(err) => {
+                *config_error.borrow_mut() = Some(err);
+                None
+            }
+        }
+    };
+
+    let file_data_list = walk_and_analyze_files(
+        walk,
+        repo_path,
+        config,
+        |entry, _, config| {
+            let context = file_context(entry.path(), config)?;
+
+            let mut file_data_list =
+                process_entry(entry, name_root, context.config(shared_config))?;
+            for file_data in &mut file_data_list {
+                file_data.package = context.package.as_ref().map(|package| package.name.clone());
+                file_data.owners = context.owners.clone();
+            }
+            Some(file_data_list)
+        },
+        |repo_path, entry, config| {
+            file_context(entry.path(), config).is_some_and(|context| {
+                is_valid_file(repo_path, entry, context.config(shared_config))
+            })
+        },
+    );
+
+    match config_error.take() {
+        Some(err) => Err(err),
+        None => Ok(file_data_list),
+    }
+}
+
+/// Analyzes several paths and/or glob patterns as a single report. A single existing path behaves
+/// exactly like `analyze`; otherwise file names are reported relative to the current directory so
+/// that they stay consistent across roots, and files matched more than once are only reported once.
+pub fn analyze_paths(
+    paths: &[String],
+    config: &FtaConfigResolved,
+) -> Result<Vec<FileData>, ConfigError> {
+    if let [path] = paths {
+        if Path::new(path).exists() {
+            return analyze(path, config);
+        }
+    }
+
+    init_logger();
+
+    analyze_paths_in(".", paths, config)
+}
+
+/// Analyzes paths and/or glob patterns relative to `cwd`, whose config is `config`. Inputs are
+/// canonicalized and reported relative to `cwd`. Those inside it are walked as part of its
+/// workspace, so that a file gets the same package and nested config files whether it's listed on
+/// its own or found by walking a directory; others are walked as projects of their own.
+fn analyze_paths_in(
+    cwd: &str,
+    paths: &[String],
+    config: &FtaConfigResolved,
+) -> Result<Vec<FileData>, ConfigError> {
+    let cwd_path = fs::canonicalize(cwd).unwrap_or_else(|_| PathBuf::from(cwd));
+    let name_root = cwd_path.display().to_string();
+    let mut context = WalkContext::new(&name_root, config);
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut file_data_list: Vec<FileData> = Vec::new();
+
+    for path in paths {
+        let input = Path::new(cwd).join(path);
+        let (root, glob) = if input.exists() {
+            (input, None)
+        } else {
+            let (base, pattern) = split_glob(path);
+            if pattern.is_empty() {
+                warn!("Skipping {}: no such file or directory", path);
+                continue;
+            }
+            match GlobBuilder::new(&pattern).literal_separator(true).build() {
+                Ok(glob) => (
+                    Path::new(cwd).join(&base),
+                    Some((base, glob.compile_matcher())),
+                ),
+                Err(err) => {
+                    warn!("Skipping {}: invalid glob pattern: {}", path, err);
+                    continue;
+                }
+            }
+        };
+        let root = match fs::canonicalize(&root) {
+            Ok(root) => root,
+            Err(err) => {
+                warn!("Skipping {}: {}", path, err);
+                continue;
+            }
+        };
+        let walk_root = if root.starts_with(&cwd_path) {
+            name_root.clone()
+        } else {
+            root.display().to_string()
+        };
+
+        let walk = build_walk(&root.display().to_string()).filter(|entry| match entry {
+            Ok(entry) => {
+                // Globs match paths as the user wrote them, relative to the base they were split at
+                let matches_glob = glob.as_ref().is_none_or(|(base, matcher)| {
+                    let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+                    matcher.is_match(get_file_name(&Path::new(base).join(relative), "."))
+                });
+                let is_file = entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_file());
+
+                matches_glob && (!is_file || seen.insert(get_file_name(entry.path(), &name_root)))
+            }
+            Err(_) => false,
+        });
+
+        file_data_list.extend(analyze_walk(walk, &walk_root, &mut context)?);
+    }
+
+    Ok(file_data_list)
+}
+
+/// Analyzes source code that doesn't (necessarily) exist on disk, e.g. an unsaved editor buffer
+/// piped through stdin. `file_name` is used for reporting and to pick the JSX setting. Taking
+/// `config` as the config of the current directory, the source gets the config a walk would give
+/// the file at `file_name`: that of the config files of its directories, the overrides matching it
+/// and its tsconfig.json.
+pub fn analyze_source_code(
+    file_name: &str,
+    source_code: &str,
+    config: &FtaConfigResolved,
+) -> Result<Vec<FileData>, ConfigError> {
+    init_logger();
+
+    analyze_source_in(".", file_name, source_code, config)
+}
+
+/// Analyzes source code as if it were the file at `file_name` under `root`, whose config is
+/// `config`. Absolute names inside `root` are made relative to it.
+fn analyze_source_in(
+    root: &str,
+    file_name: &str,
+    source_code: &str,
+    config: &FtaConfigResolved,
+) -> Result<Vec<FileData>, ConfigError> {
+    let path = Path::new(file_name);
+    let path = match fs::canonicalize(root) {
+        Ok(dir) => path.strip_prefix(dir).unwrap_or(path),
+        Err(_) => path,
+    };
+    let path = Path::new(root).join(path);
+
+    let mut context = WalkContext::new(root, config);
+    let workspace = context.workspace(root);
+    let code_owners = context.code_owners.for_root(root);
+    let file_context = FileContext::new(
+        &path,
+        root,
+        &workspace,
+        code_owners.as_deref(),
+        &context.tsconfigs,
+        config,
+    )?;
+
+    let mut file_data_list = collect_results(
+        file_name.to_string(),
+        source_code,
+        file_context.config(&context.shared_config),
+    )
+    .unwrap_or_default();
+    for file_data in &mut file_data_list {
+        file_data.package = file_context
+            .package
+            .as_ref()
+            .map(|package| package.name.clone());
+        file_data.owners = file_context.owners.clone();
+    }
+    Ok(file_data_list)
+}
+
+/// Analyzes the files of a git revision, reading blobs from the object store instead of the
+/// working directory. Works in bare repositories, e.g. from a `pre-receive` hook.
+pub fn analyze_revision(
+    repo_path: &str,
+    rev: &str,
+    config: &FtaConfigResolved,
+) -> Result<Vec<FileData>, GitError> {
+    init_logger();
+
+    let repository = GitRepository::open(repo_path)?;
+    let commit = repository.resolve_commit(rev)?;
+    let mut blobs = repository.blob_reader()?;
+    analyze_tree(&repository, &mut blobs, &commit, config, true)
+}
+
+/// Analyzes a sample of the commits since `since` (a commit, ref or date), reading every file
+/// straight from the git object store so that the working tree is left untouched.
+pub fn analyze_history(
+    repo_path: &str,
+    since: &str,
+    step: usize,
+    config: &FtaConfigResolved,
+) -> Result<Vec<HistoryPoint>, GitError> {
+    init_logger();
 
-    walk_and_analyze_files(walk, repo_path, config, process_entry, is_valid_file)
+    history::analyze_history(repo_path, since, step, config)
 }
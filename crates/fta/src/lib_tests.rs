@@ -0,0 +1,647 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::{get_default_config, read_config};
+    use crate::structs::{
+        Encoding, FileData, FtaConfigResolved, Language, ScoreLines, SuppressionKind,
+    };
+    use crate::test_utils::{git, write};
+    use crate::{
+        analyze, analyze_paths, analyze_paths_in, analyze_revision, analyze_source_code,
+        analyze_source_in, init_logger,
+    };
+    use std::fs;
+    use tempfile::TempDir;
+
+    const SOURCE: &str = r#"
+        function add(a: number, b: number): number {
+            return a + b;
+        }
+        const one = add(1, 2);
+        const two = add(3, 4);
+        const three = add(one, two);
+        console.log(one, two, three);
+    "#;
+
+    #[test]
+    fn test_analyze_revision_in_a_bare_repository() {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path().join("work");
+        fs::create_dir_all(repo.join("src")).unwrap();
+        git(&repo, &["init", "--quiet"]);
+        fs::write(repo.join("src/a.ts"), SOURCE).unwrap();
+        fs::write(repo.join("src/b.tsx"), SOURCE).unwrap();
+        fs::write(repo.join("README.md"), "# Not analyzed").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "--quiet", "-m", "first"]);
+        fs::write(repo.join("src/c.ts"), SOURCE).unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "--quiet", "-m", "second"]);
+
+        let bare = dir.path().join("repo.git");
+        git(
+            dir.path(),
+            &["clone", "--quiet", "--bare", "work", "repo.git"],
+        );
+
+        let config = get_default_config();
+        let bare_path = bare.to_str().unwrap();
+
+        let head = analyze_revision(bare_path, "HEAD", &config).unwrap();
+        let names: Vec<&str> = head.iter().map(|data| data.file_name.as_str()).collect();
+        assert_eq!(names, vec!["src/a.ts", "src/b.tsx", "src/c.ts"]);
+
+        let previous = analyze_revision(bare_path, "HEAD~1", &config).unwrap();
+        assert_eq!(previous.len(), 2);
+        assert_eq!(previous[0].fta_score, head[0].fta_score);
+
+        assert!(analyze_revision(bare_path, "does-not-exist", &config).is_err());
+    }
+
+    #[test]
+    fn test_analyze_revision_applies_nested_configs() {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path();
+        git(repo, &["init", "--quiet"]);
+        for path in ["packages/foo/a.ts", "packages/foo/b.ts", "src/c.ts"] {
+            write(repo, path, SOURCE);
+        }
+        write(
+            repo,
+            "packages/foo/fta.json",
+            r#"{ "overrides": [{ "files": ["b.ts"], "exclude_under": 1000 }] }"#,
+        );
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "--quiet", "-m", "first"]);
+
+        let names = |findings: Vec<FileData>| {
+            let mut names: Vec<String> = findings.into_iter().map(|data| data.file_name).collect();
+            names.sort();
+            names
+        };
+        let root = repo.to_str().unwrap();
+        let walked = names(analyze(root, &get_default_config()).unwrap());
+        let revision = names(analyze_revision(root, "HEAD", &get_default_config()).unwrap());
+
+        assert_eq!(walked, vec!["packages/foo/a.ts", "src/c.ts"]);
+        assert_eq!(revision, walked);
+
+        write(repo, "packages/foo/fta.json", "{ \"exclude_under\": ");
+        git(repo, &["commit", "--quiet", "-am", "second"]);
+        assert!(analyze_revision(root, "HEAD", &get_default_config()).is_err());
+    }
+
+    #[test]
+    fn test_analyze_single_file() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("foo.ts");
+        fs::write(&file, SOURCE).unwrap();
+        let file_path = file.to_str().unwrap();
+
+        let findings = analyze(file_path, &get_default_config()).unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].file_name, file_path);
+    }
+
+    #[test]
+    fn test_analyze_source_code() {
+        let config = get_default_config();
+        let jsx = "const a = <div>hello</div>;\n".repeat(8);
+
+        let findings = analyze_source_code("src/component.tsx", &jsx, &config).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].file_name, "src/component.tsx");
+        assert_eq!(findings[0].line_count, 8);
+
+        // Too short to pass the `exclude_under` option
+        assert!(analyze_source_code("short.ts", "const a = 1;", &config)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_analyze_source_resolves_config_like_the_walk() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::create_dir_all(dir.path().join("legacy")).unwrap();
+        fs::write(dir.path().join("CODEOWNERS"), "/legacy/ @org/legacy\n").unwrap();
+        fs::write(
+            dir.path().join("legacy/package.json"),
+            r#"{ "name": "legacy" }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("fta.json"),
+            r#"{ "exclude_under": 0, "overrides": [{ "files": ["tests/**"], "exclude_under": 1000 }] }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("legacy/fta.json"),
+            r#"{ "overrides": [{ "files": ["*.spec.ts"], "exclude_under": 1000 }] }"#,
+        )
+        .unwrap();
+        let config = read_config(format!("{}/fta.json", root), true).unwrap();
+
+        let findings = analyze_source_in(root, "legacy/a.ts", SOURCE, &config).unwrap();
+        assert_eq!(findings[0].file_name, "legacy/a.ts");
+        assert_eq!(findings[0].package.as_deref(), Some("legacy"));
+        assert_eq!(findings[0].owners, vec!["@org/legacy"]);
+
+        // Left out by an override of the root config and by one of the nested config
+        assert!(analyze_source_in(root, "tests/a.ts", SOURCE, &config)
+            .unwrap()
+            .is_empty());
+        assert!(analyze_source_in(root, "legacy/a.spec.ts", SOURCE, &config)
+            .unwrap()
+            .is_empty());
+        let absolute = dir.path().join("legacy/a.spec.ts");
+        assert!(
+            analyze_source_in(root, absolute.to_str().unwrap(), SOURCE, &config)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_suppressed_files_are_reported_but_skip_gates() {
+        let mut config = get_default_config();
+        // Any gated file would breach this and exit
+        config.score_cap = 0;
+
+        let source = format!("// fta-ignore-file -- vendored\n{}", SOURCE);
+        let findings = analyze_source_code("vendor.ts", &source, &config).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].suppressions[0].kind, SuppressionKind::File);
+        assert_eq!(
+            findings[0].suppressions[0].reason.as_deref(),
+            Some("vendored")
+        );
+        assert!(findings[0].fta_score > 0.0);
+
+        let source = format!("/* fta-disable score-cap */\n{}", SOURCE);
+        assert_eq!(
+            analyze_source_code("a.ts", &source, &config).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_ignored_functions_are_left_out_of_the_gate_score() {
+        let config = get_default_config();
+        let source = format!("// fta-ignore-next-function\n{}", SOURCE);
+
+        let findings = analyze_source_code("a.ts", &source, &config).unwrap();
+        assert_eq!(findings[0].suppressions[0].kind, SuppressionKind::Function);
+        assert_eq!(findings[0].suppressions[0].line, 3);
+        assert!(findings[0].gate_fta_score.unwrap() < findings[0].fta_score);
+    }
+
+    const COMPONENT: &str = r#"<template>
+  <button @click="increment">{{ count }}</button>
+</template>
+
+<script setup lang="ts">
+import { ref } from "vue";
+
+const count = ref<number>(0);
+// fta-ignore-next-function
+function increment(): void {
+    count.value++;
+}
+</script>
+"#;
+
+    #[test]
+    fn test_component_scripts_are_analyzed_when_opted_in() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("Counter.vue"), COMPONENT).unwrap();
+        let mut config = get_default_config();
+        assert!(analyze(dir.path().to_str().unwrap(), &config)
+            .unwrap()
+            .is_empty());
+
+        config.extensions.push(".vue".to_string());
+        config.exclude_under = 0;
+        let findings = analyze(dir.path().to_str().unwrap(), &config).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].file_name, "Counter.vue");
+        assert_eq!(findings[0].line_count, 5);
+        assert_eq!(findings[0].suppressions[0].line, 10);
+    }
+
+    #[test]
+    fn test_score_lines_picks_the_line_count() {
+        let mut config = get_default_config();
+        config.exclude_under = 0;
+        let source = format!("// Adds numbers\n\n{}", SOURCE);
+
+        let findings = analyze_source_code("a.ts", &source, &config).unwrap();
+        let lines = findings[0].lines.clone();
+        assert_eq!(
+            (lines.physical, lines.source, lines.comment, lines.blank),
+            (11, 7, 1, 3)
+        );
+        assert_eq!(lines.logical, 6);
+        assert_eq!(findings[0].line_count, 7);
+
+        config.score_lines = ScoreLines::Physical;
+        assert_eq!(
+            analyze_source_code("a.ts", &source, &config).unwrap()[0].line_count,
+            11
+        );
+
+        config.score_lines = ScoreLines::Logical;
+        assert_eq!(
+            analyze_source_code("a.ts", &source, &config).unwrap()[0].line_count,
+            6
+        );
+    }
+
+    #[test]
+    fn test_logical_scores_ignore_formatting() {
+        let wide = r#"
+export function pick(a: number, b: number, c: boolean): number {
+    if (a > b && c || b > a) { return a; }
+    const total = a + b + 1;
+    return total;
+}
+"#;
+        let narrow = r#"
+export function pick(
+    a: number,
+    b: number,
+    c: boolean,
+): number {
+    if ((a > b && c) || b > a) {
+        return a;
+    }
+    const total = a + b + 1;
+    return total;
+}
+"#;
+        let mut config = get_default_config();
+        config.exclude_under = 0;
+        let score = |config: &FtaConfigResolved, source: &str| {
+            analyze_source_code("a.ts", source, config).unwrap()[0].fta_score
+        };
+
+        assert_ne!(score(&config, wide), score(&config, narrow));
+
+        config.score_lines = ScoreLines::Logical;
+        assert_eq!(score(&config, wide), score(&config, narrow));
+    }
+
+    #[test]
+    fn test_parse_failures_are_reported() {
+        let mut config = get_default_config();
+        config.score_cap = 0;
+        let source = "\n\nconst a = 1;\n\nconst b = ;\n";
+
+        let findings = analyze_source_code("a.ts", source, &config).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].line_count, 2);
+        assert_eq!(findings[0].assessment, "Failed to parse");
+        assert_eq!(findings[0].syntax, Language::TypeScript);
+        let failure = findings[0].parse_failure.as_ref().unwrap();
+        assert_eq!((failure.line, failure.column), (5, 11));
+        assert!(!failure.message.is_empty());
+    }
+
+    #[test]
+    fn test_unreadable_files_are_reported() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let mut latin1 = b"// caf\xE9\n".to_vec();
+        latin1.extend_from_slice(SOURCE.as_bytes());
+        fs::write(root.join("bom.ts"), format!("\u{feff}{}", SOURCE)).unwrap();
+        fs::write(root.join("latin1.ts"), latin1).unwrap();
+        fs::write(root.join("large.ts"), SOURCE.repeat(2)).unwrap();
+        let mut config = get_default_config();
+        config.max_file_size = SOURCE.len() + 10;
+        let too_large = format!(
+            "{} bytes is larger than max_file_size ({} bytes)",
+            SOURCE.len() * 2,
+            SOURCE.len() + 10
+        );
+
+        let mut findings = analyze(root.to_str().unwrap(), &config).unwrap();
+        findings.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        let skipped: Vec<(&str, Option<&str>)> = findings
+            .iter()
+            .map(|data| (data.file_name.as_str(), data.skipped.as_deref()))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                ("bom.ts", None),
+                ("large.ts", Some(too_large.as_str())),
+                (
+                    "latin1.ts",
+                    Some("not valid UTF-8, set `encoding` to read it as another encoding")
+                ),
+            ]
+        );
+        assert_eq!(findings[0].lines.physical, SOURCE.lines().count());
+
+        config.encoding = Encoding::Latin1;
+        let findings = analyze(&root.join("latin1.ts").to_string_lossy(), &config).unwrap();
+        assert!(findings[0].skipped.is_none());
+        assert!(findings[0].parse_failure.is_none());
+    }
+
+    #[test]
+    fn test_generated_files_are_skipped_unless_included() {
+        let mut config = get_default_config();
+        let source = format!("// @generated by graphql-codegen\n{}", SOURCE);
+
+        let findings = analyze_source_code("graphql.ts", &source, &config).unwrap();
+        assert_eq!(
+            findings[0].skipped.as_deref(),
+            Some("generated, its header says \"@generated\"")
+        );
+        assert_eq!(findings[0].assessment, "Skipped");
+
+        config.include_generated = true;
+        let findings = analyze_source_code("graphql.ts", &source, &config).unwrap();
+        assert!(findings[0].skipped.is_none());
+        assert!(findings[0].cyclo > 0);
+    }
+
+    #[test]
+    fn test_slow_files_fail_without_stopping_the_run() {
+        let mut config = get_default_config();
+        config.file_timeout_ms = Some(1);
+        let source = SOURCE.repeat(2000);
+
+        let findings = analyze_source_code("slow.ts", &source, &config).unwrap();
+        assert_eq!(
+            findings[0].analysis_failure.as_deref(),
+            Some("took longer than 1ms")
+        );
+        assert_eq!(findings[0].assessment, "Failed to analyze");
+        assert!(!findings[0].is_analyzed());
+
+        config.file_timeout_ms = None;
+        let findings = analyze_source_code("slow.ts", &source, &config).unwrap();
+        assert!(findings[0].is_analyzed());
+    }
+
+    const DECORATED: &str = r#"
+        @Controller("cats")
+        export class CatsController {
+            constructor(private readonly cats: CatsService) {}
+
+            @Get(":id")
+            findOne(@Param("id") id: string) {
+                return this.cats.findOne(id);
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_parser_options_come_from_tsconfig() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("api/src")).unwrap();
+        fs::create_dir_all(root.join("web")).unwrap();
+        fs::write(root.join("api/src/cats.controller.ts"), DECORATED).unwrap();
+        fs::write(root.join("web/cats.controller.ts"), DECORATED).unwrap();
+        fs::write(
+            root.join("api/tsconfig.json"),
+            r#"{ "compilerOptions": { "experimentalDecorators": true } }"#,
+        )
+        .unwrap();
+        let mut config = get_default_config();
+
+        let mut findings = analyze(root.to_str().unwrap(), &config).unwrap();
+        findings.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].file_name, "api/src/cats.controller.ts");
+        assert!(findings[0].parse_failure.is_none());
+        assert_eq!(findings[1].file_name, "web/cats.controller.ts");
+        assert!(findings[1].parse_failure.is_some());
+
+        // Parser options can be set in the config too, taking precedence over tsconfig.json
+        config.parser.decorators = Some(true);
+        let findings = analyze(root.to_str().unwrap(), &config).unwrap();
+        assert!(findings
+            .iter()
+            .all(|file_data| file_data.parse_failure.is_none()));
+    }
+
+    #[test]
+    fn test_analyze_paths_merges_roots_and_globs() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::create_dir_all(dir.path().join("a/src")).unwrap();
+        fs::create_dir_all(dir.path().join("b/lib")).unwrap();
+        fs::write(dir.path().join("a/src/x.ts"), SOURCE).unwrap();
+        fs::write(dir.path().join("b/lib/y.tsx"), SOURCE).unwrap();
+        fs::write(dir.path().join("b/lib/z.ts"), SOURCE).unwrap();
+
+        // Absolute and relative paths to the same file are reported once, relative to the cwd
+        let paths = vec![
+            "a".to_string(),
+            format!("{}/a/src/x.ts", root),
+            "./a/src/x.ts".to_string(),
+            format!("{}/b/**/*.tsx", root),
+            "missing".to_string(),
+        ];
+        let mut names: Vec<String> = analyze_paths_in(root, &paths, &get_default_config())
+            .unwrap()
+            .into_iter()
+            .map(|data| data.file_name)
+            .collect();
+        names.sort();
+
+        assert_eq!(names, vec!["a/src/x.ts", "b/lib/y.tsx"]);
+
+        // Paths outside the cwd are reported under their canonical path
+        let outside = fs::canonicalize(dir.path().join("b")).unwrap();
+        let paths = vec![outside.display().to_string()];
+        let findings =
+            analyze_paths_in(&format!("{}/a", root), &paths, &get_default_config()).unwrap();
+        assert_eq!(
+            findings[0].file_name,
+            outside.join("lib/z.ts").display().to_string()
+        );
+    }
+
+    #[test]
+    fn test_analyze_paths_gives_listed_files_their_package_and_config() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        for path in ["packages/foo/a.ts", "packages/foo/b.ts", "src/c.ts"] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, SOURCE).unwrap();
+        }
+        fs::write(
+            dir.path().join("packages/foo/package.json"),
+            r#"{ "name": "foo" }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("packages/foo/fta.json"),
+            r#"{ "overrides": [{ "files": ["b.ts"], "exclude_under": 1000 }] }"#,
+        )
+        .unwrap();
+
+        let summarize = |findings: Vec<FileData>| {
+            let mut files: Vec<(String, Option<String>)> = findings
+                .into_iter()
+                .map(|data| (data.file_name, data.package))
+                .collect();
+            files.sort();
+            files
+        };
+        let walked = summarize(analyze(root, &get_default_config()).unwrap());
+        let paths = ["packages/foo/a.ts", "packages/foo/b.ts", "src/c.ts"].map(String::from);
+        let listed = summarize(analyze_paths_in(root, &paths, &get_default_config()).unwrap());
+
+        assert_eq!(
+            walked,
+            vec![
+                ("packages/foo/a.ts".to_string(), Some("foo".to_string())),
+                ("src/c.ts".to_string(), None),
+            ]
+        );
+        assert_eq!(listed, walked);
+    }
+
+    #[test]
+    fn test_analyze_paths_with_a_single_directory_keeps_relative_names() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/x.ts"), SOURCE).unwrap();
+
+        let findings = analyze_paths(
+            &[dir.path().to_str().unwrap().to_string()],
+            &get_default_config(),
+        )
+        .unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].file_name, "src/x.ts");
+    }
+
+    #[test]
+    fn test_analyze_tags_owners_and_applies_owner_overrides() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::create_dir_all(dir.path().join("web")).unwrap();
+        fs::create_dir_all(dir.path().join("api")).unwrap();
+        fs::write(dir.path().join("web/x.ts"), SOURCE).unwrap();
+        fs::write(dir.path().join("api/y.ts"), SOURCE).unwrap();
+        fs::write(
+            dir.path().join("CODEOWNERS"),
+            "/web/ @org/web\n/api/ @org/api @alice\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("fta.json"),
+            r#"{ "overrides": [{ "owners": ["@org/web"], "exclude_under": 1000 }] }"#,
+        )
+        .unwrap();
+
+        let config = read_config(format!("{}/fta.json", root), true).unwrap();
+        let findings = analyze(root, &config).unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].file_name, "api/y.ts");
+        assert_eq!(findings[0].owners, vec!["@org/api", "@alice"]);
+    }
+
+    #[test]
+    fn test_analyze_resolves_config_per_file() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        for path in ["src/x.ts", "tests/y.ts", "legacy/z.ts", "legacy/keep/w.ts"] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, SOURCE).unwrap();
+        }
+        fs::write(
+            dir.path().join("fta.json"),
+            r#"{ "overrides": [{ "files": ["tests/**"], "exclude_under": 1000 }] }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("legacy/fta.json"),
+            r#"{ "exclude_under": 1000, "overrides": [{ "files": ["keep/**"], "exclude_under": 0 }] }"#,
+        )
+        .unwrap();
+
+        let config = read_config(format!("{}/fta.json", root), true).unwrap();
+        let mut names: Vec<String> = analyze(root, &config)
+            .unwrap()
+            .into_iter()
+            .map(|data| data.file_name)
+            .collect();
+        names.sort();
+
+        assert_eq!(names, vec!["legacy/keep/w.ts", "src/x.ts"]);
+    }
+
+    #[test]
+    fn test_analyze_fails_on_invalid_nested_configs() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::create_dir_all(dir.path().join("legacy")).unwrap();
+        fs::write(dir.path().join("x.ts"), SOURCE).unwrap();
+        fs::write(dir.path().join("legacy/y.ts"), SOURCE).unwrap();
+        fs::write(dir.path().join("legacy/fta.json"), "{ \"exclude_under\": ").unwrap();
+
+        let error = analyze(root, &get_default_config()).unwrap_err();
+        assert!(error.to_string().contains("legacy"));
+
+        let paths = vec![format!("{}/**/*.ts", root)];
+        assert!(analyze_paths(&paths, &get_default_config()).is_err());
+    }
+
+    #[test]
+    fn test_analyze_honours_ftaignore_files() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        for path in [
+            "src/x.ts",
+            "src/legacy.ts",
+            "src/api/generated/client.ts",
+            "scripts/build.ts",
+        ] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, SOURCE).unwrap();
+        }
+        fs::write(dir.path().join(".ftaignore"), "/scripts/\nlegacy.ts\n").unwrap();
+        fs::write(dir.path().join("src/api/.ftaignore"), "generated/\n").unwrap();
+
+        let mut names: Vec<String> = analyze(root, &get_default_config())
+            .unwrap()
+            .into_iter()
+            .map(|data| data.file_name)
+            .collect();
+        names.sort();
+
+        assert_eq!(names, vec!["src/x.ts"]);
+
+        // The .ftaignore files of a revision exclude the same files
+        git(dir.path(), &["init", "--quiet"]);
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "--quiet", "-m", "first"]);
+        let names: Vec<String> = analyze_revision(root, "HEAD", &get_default_config())
+            .unwrap()
+            .into_iter()
+            .map(|data| data.file_name)
+            .collect();
+        assert_eq!(names, vec!["src/x.ts"]);
+    }
+
+    #[test]
+    fn test_init_logger_shows_warnings() {
+        // The CLI relies on this to show warnings about its config before analyzing anything
+        init_logger();
+
+        assert!(log::log_enabled!(log::Level::Warn));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::structs::LineCounts;
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+mod tests;
+
+/// What a line of a file holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// Nothing but whitespace, even inside a block comment
+    Blank,
+    Code,
+    Comment,
+    /// Both code and a comment
+    Mixed,
+}
+
+/// Classifies the lines of `source`, given the byte ranges of its comments. Anything that isn't
+/// whitespace or part of a comment is code.
+pub fn classify(source: &str, comments: &[(usize, usize)]) -> Vec<LineKind> {
+    let mut comments = comments.to_vec();
+    comments.sort_unstable();
+    let mut comments = comments.into_iter().peekable();
+
+    let kind = |has_code: bool, has_comment: bool| match (has_code, has_comment) {
+        (false, false) => LineKind::Blank,
+        (true, false) => LineKind::Code,
+        (false, true) => LineKind::Comment,
+        (true, true) => LineKind::Mixed,
+    };
+
+    let mut kinds = Vec::new();
+    let (mut has_code, mut has_comment) = (false, false);
+    let mut comment_end = 0;
+    for (index, c) in source.char_indices() {
+        if c == '\n' {
+            kinds.push(kind(has_code, has_comment));
+            (has_code, has_comment) = (false, false);
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+
+        while let Some((_, end)) = comments.next_if(|&(start, _)| start <= index) {
+            comment_end = comment_end.max(end);
+        }
+        if index < comment_end {
+            has_comment = true;
+        } else {
+            has_code = true;
+        }
+    }
+    // Like `str::lines`, a final line break doesn't start another line
+    if !source.is_empty() && !source.ends_with('\n') {
+        kinds.push(kind(has_code, has_comment));
+    }
+
+    kinds
+}
+
+/// Adds up the lines of each kind, along with the logical lines counted from the AST
+pub fn count_lines(kinds: &[LineKind], logical: usize) -> LineCounts {
+    let count = |kind: LineKind| kinds.iter().filter(|&&line| line == kind).count();
+
+    LineCounts {
+        physical: kinds.len(),
+        source: count(LineKind::Code) + count(LineKind::Mixed),
+        comment: count(LineKind::Comment),
+        blank: count(LineKind::Blank),
+        mixed: count(LineKind::Mixed),
+        logical,
+    }
+}
+
+struct StatementVisitor {
+    count: usize,
+}
+
+impl Visit for StatementVisitor {
+    fn visit_stmt(&mut self, node: &Stmt) {
+        // Blocks only group other statements
+        if !matches!(node, Stmt::Block(_) | Stmt::Empty(_)) {
+            self.count += 1;
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_module_decl(&mut self, node: &ModuleDecl) {
+        self.count += 1;
+        node.visit_children_with(self);
+    }
+}
+
+/// Counts the statements and declarations of a module, including those nested in functions
+pub fn logical_lines(module: &Module) -> usize {
+    let mut visitor = StatementVisitor { count: 0 };
+    module.visit_with(&mut visitor);
+    visitor.count
+}
+
+struct ParenRemover;
+
+impl VisitMut for ParenRemover {
+    fn visit_mut_expr(&mut self, node: &mut Expr) {
+        node.visit_mut_children_with(self);
+        if let Expr::Paren(paren) = node {
+            *node = std::mem::replace(
+                &mut *paren.expr,
+                Expr::Invalid(Invalid { span: paren.span }),
+            );
+        }
+    }
+}
+
+/// Unwraps grouping parentheses, which formatters add and remove without changing the code. The
+/// tree already holds the precedence they express.
+pub fn remove_parens(module: &mut Module) {
+    module.visit_mut_with(&mut ParenRemover);
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use crate::lines::{classify, count_lines, logical_lines, LineKind};
+    use crate::parse::{dialect_for, parse_source};
+    use crate::structs::{LineCounts, ParserOptions};
+
+    /// The byte ranges of `comments`, which must each appear once in `source`
+    fn ranges(source: &str, comments: &[&str]) -> Vec<(usize, usize)> {
+        comments
+            .iter()
+            .map(|comment| {
+                let start = source.find(comment).unwrap();
+                (start, start + comment.len())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_classify() {
+        let source = "/**\n *\n\n * Docs\n */\nconst a = 1; // one\n\n  const b = /* two */ 2;\n/* a */ /* b */\nreturn;";
+        let comments = ranges(
+            source,
+            &[
+                "/**\n *\n\n * Docs\n */",
+                "// one",
+                "/* two */",
+                "/* a */",
+                "/* b */",
+            ],
+        );
+
+        assert_eq!(
+            classify(source, &comments),
+            vec![
+                LineKind::Comment,
+                LineKind::Comment,
+                LineKind::Blank,
+                LineKind::Comment,
+                LineKind::Comment,
+                LineKind::Mixed,
+                LineKind::Blank,
+                LineKind::Mixed,
+                LineKind::Comment,
+                LineKind::Code,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_classify_handles_line_endings() {
+        assert_eq!(classify("", &[]), vec![]);
+        assert_eq!(
+            classify("a;\r\n\r\nb;\n", &[]),
+            vec![LineKind::Code, LineKind::Blank, LineKind::Code]
+        );
+    }
+
+    #[test]
+    fn test_count_lines() {
+        let kinds = [
+            LineKind::Code,
+            LineKind::Mixed,
+            LineKind::Comment,
+            LineKind::Blank,
+            LineKind::Code,
+        ];
+
+        assert_eq!(
+            count_lines(&kinds, 4),
+            LineCounts {
+                physical: 5,
+                source: 3,
+                comment: 1,
+                blank: 1,
+                mixed: 1,
+                logical: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn test_logical_lines() {
+        let source = r#"
+            import { a } from "a";
+
+            export function add(
+                x: number,
+                y: number,
+            ): number {
+                if (x > y) { return x + y; }
+                return y + x;
+            }
+            const sum = add(1, 2); const twice = sum * 2;
+        "#;
+        let parsed = parse_source(
+            "a.ts",
+            source,
+            dialect_for("a.ts", &ParserOptions::default()),
+            false,
+            &ParserOptions::default(),
+        );
+
+        // The import, export, if, both returns and both consts
+        assert_eq!(logical_lines(parsed.module.as_ref().unwrap()), 7);
+        assert_eq!(parsed.lines.logical, 7);
+        assert_eq!(parsed.lines.source, 9);
+    }
+}
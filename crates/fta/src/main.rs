@@ -1,25 +1,94 @@
-use clap::Parser;
-use fta::analyze;
-use fta::config::read_config;
+use clap::{Parser, Subcommand};
+use fta::config::find_config_in;
+use fta::config::{
+    apply_setting, discover_config, env_settings, format_config, parse_setting, ConfigSources,
+    FtaConfigResolved,
+};
+use fta::init::{scaffold_config, DEFAULT_PERCENTILE};
+use fta::{analyze_history, analyze_paths, analyze_revision, analyze_source_code, init_logger};
+use std::fs::File;
+use std::io::{self, Read};
+use std::num::NonZeroUsize;
+use std::path::Path;
 use std::time::Instant;
 
 #[cfg(feature = "use_output")]
-use fta::output::generate_output;
+use fta::group::{group_by_owner, group_by_package};
+#[cfg(feature = "use_output")]
+use fta::output::{generate_group_output, generate_output};
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    subcommand_negates_reqs = true,
+    args_conflicts_with_subcommands = true
+)]
 struct Cli {
-    #[arg(required = true, help = "Path to the project to analyze")]
-    project: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(
+        required_unless_present_any = ["stdin", "files_from", "print_config"],
+        help = "Paths or glob patterns of the projects or files to analyze"
+    )]
+    project: Vec<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "rev",
+        help = "Read the paths to analyze from a file, or from stdin with -. One path per line, or NUL-separated."
+    )]
+    files_from: Option<String>,
 
-    #[arg(long, short, help = "Path to config file")]
+    #[arg(
+        long,
+        conflicts_with_all = ["project", "rev", "files_from"],
+        help = "Analyze source code piped through stdin instead of a project"
+    )]
+    stdin: bool,
+
+    #[arg(
+        long,
+        requires = "stdin",
+        default_value = "stdin.ts",
+        help = "File name to report stdin source code under, also used to detect JSX and to pick the config that applies to it (default: stdin.ts)"
+    )]
+    stdin_filename: String,
+
+    #[arg(
+        long,
+        short,
+        help = "Path to config file. By default the first fta.json, .ftarc.json, fta.yaml, fta.yml, fta.toml or package.json with an \"fta\" key found in the project or its parent directories is used, in that order of precedence"
+    )]
     config_path: Option<String>,
 
+    #[arg(
+        long = "set",
+        value_name = "OPTION=VALUE",
+        help = "Set a config option, e.g. score_cap=70, or add to a list option, e.g. extensions+=.mts. Can be repeated. Takes precedence over config files and FTA_* environment variables, e.g. FTA_SCORE_CAP=70"
+    )]
+    settings: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Print the resolved config and where each value came from, then exit"
+    )]
+    print_config: bool,
+
+    #[arg(
+        long,
+        help = "Analyze the files of a git revision instead of the working directory, e.g. HEAD or a commit id. Works in bare repositories."
+    )]
+    rev: Option<String>,
+
     #[arg(
         long,
         short,
         default_value = "table",
-        value_parser(["table", "csv", "json"]),
+        value_parser(["table", "csv", "json", "markdown"]),
         help = "Output format (default: table)",
         conflicts_with = "json"
     )]
@@ -28,6 +97,13 @@ struct Cli {
     #[arg(long, help = "Output as JSON.", conflicts_with = "format")]
     json: bool,
 
+    #[arg(
+        long,
+        value_parser(["package", "owner"]),
+        help = "Output a summary per group instead of per file. `package` groups files by their npm/yarn/pnpm workspace package, `owner` by their CODEOWNERS owners"
+    )]
+    group_by: Option<String>,
+
     #[arg(
         long,
         short,
@@ -42,6 +118,12 @@ struct Cli {
     )]
     score_cap: Option<usize>,
 
+    #[arg(
+        long,
+        help = "Exit with an error if any file fails to parse or analyze, after reporting the results"
+    )]
+    strict: bool,
+
     #[arg(
         long,
         short,
@@ -57,63 +139,339 @@ struct Cli {
     exclude_under: Option<usize>,
 }
 
-pub fn main() {
-    // Start tracking execution time
-    let start = Instant::now();
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[command(
+        about = "Analyze past commits straight from the git object store and output a JSON time series"
+    )]
+    History {
+        #[arg(required = true, help = "Path to the git repository to analyze")]
+        project: String,
 
-    let cli = Cli::parse();
+        #[arg(
+            long,
+            help = "Commit, ref or date to start from, e.g. v1.0.0 or 2024-01-01"
+        )]
+        since: String,
+
+        #[arg(
+            long,
+            default_value = "1",
+            help = "Analyze every nth commit, always including HEAD (default: 1)"
+        )]
+        step: NonZeroUsize,
+
+        #[arg(long, short, help = "Path to config file")]
+        config_path: Option<String>,
+    },
+    #[command(
+        about = "Analyze a project and write an fta.json tuned to it, with a score cap that passes today"
+    )]
+    Init {
+        #[arg(default_value = ".", help = "Path to the project (default: .)")]
+        project: String,
+
+        #[arg(
+            long,
+            default_value_t = DEFAULT_PERCENTILE,
+            value_parser = clap::value_parser!(u8).range(1..=100),
+            help = "Percentile of the current FTA scores to set the score cap at. Files scoring above it get their own cap at their current score (default: 95)"
+        )]
+        percentile: u8,
+
+        #[arg(long, help = "Overwrite the project's existing config file")]
+        force: bool,
+    },
+}
 
-    // Resolve the fta.json path, which can optionally be used-supplied
-    let (config_path, path_specified_by_user) = match cli.config_path {
-        Some(config_path_arg) => (config_path_arg, true),
-        None => (format!("{}/fta.json", cli.project), false),
+fn resolve_config(
+    project: &str,
+    config_path: Option<String>,
+    settings: &[String],
+) -> (FtaConfigResolved, ConfigSources) {
+    // When analyzing a single file, look for the config next to it, then in parent directories.
+    // Globs and multiple paths use the config of the current directory.
+    let project_path = Path::new(project);
+    let config_dir = if project_path.is_file() {
+        project_path.parent().unwrap_or(Path::new(""))
+    } else if project_path.is_dir() {
+        project_path
+    } else {
+        Path::new("")
     };
 
-    // Resolve the input config. Optionally adds fta.json values to the default config.
-    let mut config = match read_config(config_path, path_specified_by_user) {
-        Ok(config) => config,
+    // Resolve the input config. Optionally adds the config file's values to the default config,
+    // then the FTA_* environment variables and --set values.
+    let cli_settings = settings
+        .iter()
+        .map(|setting| (setting.clone(), "--set".to_string()));
+    let resolved =
+        discover_config(config_dir, config_path).and_then(|(mut config, mut sources)| {
+            for (setting, source) in env_settings(std::env::vars())
+                .into_iter()
+                .chain(cli_settings)
+            {
+                let provided_config = parse_setting(&setting, &source)?;
+                config = apply_setting(&config, &mut sources, provided_config, &source)?;
+            }
+            Ok((config, sources))
+        });
+
+    match resolved {
+        Ok(resolved) => resolved,
         Err(err) => {
             eprintln!("{}", err);
             std::process::exit(1);
         }
+    }
+}
+
+/// Reads a list of paths, either NUL-separated or one per line
+fn read_file_list(files_from: &str) -> io::Result<Vec<String>> {
+    let mut content = String::new();
+    if files_from == "-" {
+        io::stdin().read_to_string(&mut content)?;
+    } else {
+        File::open(files_from)?.read_to_string(&mut content)?;
+    }
+
+    let separator = if content.contains('\0') { '\0' } else { '\n' };
+
+    Ok(content
+        .split(separator)
+        .map(|path| path.trim_end_matches('\r'))
+        .filter(|path| !path.trim().is_empty())
+        .map(|path| path.to_string())
+        .collect())
+}
+
+/// The first of `paths` that doesn't exist, glob patterns aside as they may match nothing
+fn missing_path(paths: &[String]) -> Option<&String> {
+    paths
+        .iter()
+        .find(|path| !Path::new(path).exists() && !path.contains(['*', '?', '[', '{']))
+}
+
+fn run_history(project: String, since: String, step: NonZeroUsize, config_path: Option<String>) {
+    let (config, _) = resolve_config(&project, config_path, &[]);
+
+    match analyze_history(&project, &since, step.get(), &config) {
+        Ok(history) => println!("{}", serde_json::to_string(&history).unwrap()),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_init(project: String, percentile: u8, force: bool) {
+    if let Some(existing) = find_config_in(Path::new(&project)) {
+        if !force {
+            eprintln!(
+                "{} already exists, use --force to write a new fta.json anyway",
+                existing.display()
+            );
+            std::process::exit(1);
+        }
+    }
+
+    let scaffold = match scaffold_config(&project, percentile) {
+        Ok(scaffold) => scaffold,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    let config_path = Path::new(&project).join("fta.json");
+    let content = serde_json::to_string_pretty(&scaffold).unwrap() + "\n";
+
+    match std::fs::write(&config_path, content) {
+        Ok(()) => println!(
+            "Wrote {} with a score cap of {} and {} file-specific caps",
+            config_path.display(),
+            scaffold
+                .score_cap
+                .map(|score_cap| score_cap.to_string())
+                .unwrap_or_else(|| "the default".to_string()),
+            scaffold
+                .overrides
+                .map(|overrides| overrides.len())
+                .unwrap_or(0)
+        ),
+        Err(err) => {
+            eprintln!("Unable to write {}: {}", config_path.display(), err);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn main() {
+    // Start tracking execution time
+    let start = Instant::now();
+
+    let cli = Cli::parse();
+
+    // Config discovery and FTA_* variables warn before any analysis starts
+    init_logger();
+
+    match cli.command {
+        Some(Command::History {
+            project,
+            since,
+            step,
+            config_path,
+        }) => {
+            run_history(project, since, step, config_path);
+            return;
+        }
+        Some(Command::Init {
+            project,
+            percentile,
+            force,
+        }) => {
+            run_init(project, percentile, force);
+            return;
+        }
+        None => {}
+    }
+
+    let mut paths = cli.project;
+    if let Some(files_from) = cli.files_from {
+        match read_file_list(&files_from) {
+            Ok(files) => paths.extend(files),
+            Err(err) => {
+                eprintln!("Unable to read file list {}: {}", files_from, err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(path) = missing_path(&paths) {
+        eprintln!("Unable to analyze {}: no such file or directory", path);
+        std::process::exit(1);
+    }
+
+    let project = match &paths[..] {
+        [path] => path.clone(),
+        _ => ".".to_string(),
     };
+    let (mut config, mut sources) = resolve_config(&project, cli.config_path, &cli.settings);
 
     // Override config with CLI args where allowed + values are provided
     if let Some(value) = cli.output_limit {
         config.output_limit = value;
+        config.command_line.output_limit = Some(value);
+        sources.set("output_limit", "--output-limit");
     }
     if let Some(value) = cli.score_cap {
         config.score_cap = value;
+        config.command_line.score_cap = Some(value);
+        sources.set("score_cap", "--score-cap");
     }
     if let Some(value) = cli.include_comments {
         config.include_comments = value;
+        config.command_line.include_comments = Some(value);
+        sources.set("include_comments", "--include-comments");
     }
     if let Some(value) = cli.exclude_under {
         config.exclude_under = value;
+        config.command_line.exclude_under = Some(value);
+        sources.set("exclude_under", "--exclude-under");
+    }
+
+    if cli.print_config {
+        println!("{}", format_config(&config, &sources));
+        return;
     }
 
     // Execute the analysis
-    let mut findings = analyze(&cli.project, &config);
+    let mut findings = match cli.rev {
+        Some(_) if paths.len() != 1 => {
+            eprintln!(
+                "--rev requires exactly one path to a git repository or a directory inside one"
+            );
+            std::process::exit(1);
+        }
+        Some(_) if !Path::new(&project).is_dir() => {
+            eprintln!(
+                "--rev requires a path to a git repository or a directory inside one, {} is a file",
+                project
+            );
+            std::process::exit(1);
+        }
+        Some(rev) => match analyze_revision(&project, &rev, &config) {
+            Ok(findings) => findings,
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        },
+        None if cli.stdin => {
+            let mut source_code = String::new();
+            if let Err(err) = io::stdin().read_to_string(&mut source_code) {
+                eprintln!("Unable to read stdin: {}", err);
+                std::process::exit(1);
+            }
+            match analyze_source_code(&cli.stdin_filename, &source_code, &config) {
+                Ok(findings) => findings,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => match analyze_paths(&paths, &config) {
+            Ok(findings) => findings,
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        },
+    };
 
     // Sort the result for display
-    findings.sort_unstable_by(|a, b| b.fta_score.partial_cmp(&a.fta_score).unwrap());
+    findings.sort_unstable_by(|a, b| b.fta_score.total_cmp(&a.fta_score));
 
     // Execution finished, capture elapsed time
     let elapsed = start.elapsed().as_secs_f64();
     #[cfg(feature = "use_output")]
     {
+        let format = if cli.json {
+            "json".to_string()
+        } else {
+            cli.format
+        };
+
         // Format and display the results
-        let output = generate_output(
-            &findings,
-            if cli.json {
-                "json".to_string()
-            } else {
-                cli.format
-            },
-            &elapsed,
-            config.output_limit,
-        );
+        let output = match cli.group_by.as_deref() {
+            Some("package") => generate_group_output(
+                &group_by_package(&findings),
+                "Package",
+                format,
+                &elapsed,
+                config.output_limit,
+            ),
+            Some("owner") => generate_group_output(
+                &group_by_owner(&findings),
+                "Owner",
+                format,
+                &elapsed,
+                config.output_limit,
+            ),
+            _ => generate_output(&findings, format, &elapsed, config.output_limit),
+        };
 
         println!("{}", output);
     }
+
+    let failures = findings
+        .iter()
+        .filter(|file_data| {
+            file_data.parse_failure.is_some() || file_data.analysis_failure.is_some()
+        })
+        .count();
+    if cli.strict && failures > 0 {
+        eprintln!("{} files failed to parse or analyze, exiting.", failures);
+        std::process::exit(1);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::structs::FileData;
+use crate::structs::{FileData, GroupSummary, SuppressionKind};
 use comfy_table::{presets::UTF8_FULL, Table};
 
 mod tests;
@@ -11,6 +11,72 @@ pub fn truncate_string(input: &str, max_length: usize) -> String {
     }
 }
 
+/// The assessment shown in the table, noting any file-wide suppression and its reason
+fn table_assessment(file_data: &FileData) -> String {
+    let suppression = file_data
+        .suppressions
+        .iter()
+        .find(|suppression| suppression.kind == SuppressionKind::File);
+
+    match suppression {
+        Some(suppression) => match &suppression.reason {
+            Some(reason) => format!("{} (suppressed: {})", file_data.assessment, reason),
+            None => format!("{} (suppressed)", file_data.assessment),
+        },
+        None => file_data.assessment.clone(),
+    }
+}
+
+/// Quotes a CSV field if it holds a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escapes the characters that would break a Markdown table cell
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+/// The number of files of a group, noting the ones that weren't analyzed in full
+fn group_file_count(group: &GroupSummary) -> String {
+    let notes: Vec<String> = [
+        (group.parse_failures, "failed to parse"),
+        (group.analysis_failures, "failed to analyze"),
+        (group.skipped, "skipped"),
+    ]
+    .iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, note)| format!("{} {}", count, note))
+    .collect();
+
+    if notes.is_empty() {
+        group.file_count.to_string()
+    } else {
+        format!("{} ({})", group.file_count, notes.join(", "))
+    }
+}
+
+/// The assessment of a file, or why it wasn't analyzed
+fn assessment(file_data: &FileData) -> String {
+    if let Some(reason) = &file_data.skipped {
+        return format!("Skipped: {}", reason);
+    }
+    if let Some(reason) = &file_data.analysis_failure {
+        return format!("Failed to analyze: {}", reason);
+    }
+    match &file_data.parse_failure {
+        Some(failure) => format!(
+            "Failed to parse at {}:{}: {}",
+            failure.line, failure.column, failure.message
+        ),
+        None => file_data.assessment.clone(),
+    }
+}
+
 pub fn generate_output(
     file_data_list: &Vec<FileData>,
     format: String,
@@ -26,12 +92,35 @@ pub fn generate_output(
         Some("csv") => {
             output.push_str("File,Num. lines,FTA Score (Lower is better),Assessment");
             for file_data in file_data_list {
+                let score = if file_data.is_analyzed() {
+                    format!("{:.2}", file_data.fta_score)
+                } else {
+                    String::new()
+                };
                 output.push_str(&format!(
-                    "\n{},{},{:.2},{}",
-                    file_data.file_name,
+                    "\n{},{},{},{}",
+                    csv_field(&file_data.file_name),
                     file_data.line_count,
-                    file_data.fta_score,
-                    file_data.assessment
+                    score,
+                    csv_field(&assessment(file_data))
+                ));
+            }
+        }
+        Some("markdown") => {
+            output.push_str("| File | Num. lines | FTA Score (Lower is better) | Assessment |");
+            output.push_str("\n| --- | ---: | ---: | --- |");
+            for file_data in file_data_list {
+                let score = if file_data.is_analyzed() {
+                    format!("{:.2}", file_data.fta_score)
+                } else {
+                    String::new()
+                };
+                output.push_str(&format!(
+                    "\n| {} | {} | {} | {} |",
+                    markdown_cell(&file_data.file_name),
+                    file_data.line_count,
+                    score,
+                    markdown_cell(&assessment(file_data))
                 ));
             }
         }
@@ -46,7 +135,18 @@ pub fn generate_output(
                 "Assessment",
             ]);
 
-            for file_data in file_data_list {
+            // Files that weren't analyzed are listed after the table instead
+            let (skipped, file_data_list): (Vec<&FileData>, Vec<&FileData>) = file_data_list
+                .iter()
+                .partition(|file_data| file_data.skipped.is_some());
+            let (interrupted, file_data_list): (Vec<&FileData>, Vec<&FileData>) = file_data_list
+                .into_iter()
+                .partition(|file_data| file_data.analysis_failure.is_some());
+            let (failed, analyzed): (Vec<&FileData>, Vec<&FileData>) = file_data_list
+                .into_iter()
+                .partition(|file_data| file_data.parse_failure.is_some());
+
+            for file_data in &analyzed {
                 if table.row_iter().count() >= output_limit {
                     continue;
                 }
@@ -54,14 +154,149 @@ pub fn generate_output(
                     truncate_string(&file_data.file_name, 50),
                     file_data.line_count.to_string(),
                     format!("{:.2}", file_data.fta_score),
-                    file_data.assessment.clone().to_string(),
+                    table_assessment(file_data),
+                ]);
+            }
+
+            output = table.to_string();
+            if !skipped.is_empty() {
+                output.push_str(&format!("\n{} files skipped:", skipped.len()));
+                for file_data in &skipped {
+                    output.push_str(&format!(
+                        "\n  {}: {}",
+                        file_data.file_name,
+                        file_data.skipped.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+            if !interrupted.is_empty() {
+                output.push_str(&format!("\n{} files failed to analyze:", interrupted.len()));
+                for file_data in &interrupted {
+                    output.push_str(&format!(
+                        "\n  {}: {}",
+                        file_data.file_name,
+                        file_data.analysis_failure.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+            if !failed.is_empty() {
+                output.push_str(&format!("\n{} files failed to parse:", failed.len()));
+                for file_data in &failed {
+                    output.push_str(&format!(
+                        "\n  {}: {}",
+                        file_data.file_name,
+                        assessment(file_data)
+                    ));
+                }
+            }
+            output.push_str(&format!(
+                "\n{} files analyzed in {}s.",
+                analyzed.len(),
+                (elapsed * 10000.0).round() / 10000.0
+            ));
+            if !failed.is_empty() {
+                output.push_str(&format!(" {} files failed to parse.", failed.len()));
+            }
+            if !interrupted.is_empty() {
+                output.push_str(&format!(" {} files failed to analyze.", interrupted.len()));
+            }
+            if !skipped.is_empty() {
+                output.push_str(&format!(" {} files skipped.", skipped.len()));
+            }
+        }
+        _ => output.push_str("No output format specified."),
+    }
+
+    output
+}
+
+pub fn generate_group_output(
+    groups: &Vec<GroupSummary>,
+    group_label: &str,
+    format: String,
+    elapsed: &f64,
+    output_limit: usize,
+) -> String {
+    let mut output = String::new();
+
+    match Some(format.as_str()) {
+        Some("json") => {
+            output = serde_json::to_string(groups).unwrap();
+        }
+        Some("csv") => {
+            output.push_str(&format!(
+                "{},Num. files,Parse failures,Analysis failures,Skipped,Num. lines,Average FTA Score,Weighted FTA Score,Worst files",
+                group_label
+            ));
+            for group in groups {
+                output.push_str(&format!(
+                    "\n{},{},{},{},{},{},{:.2},{:.2},{}",
+                    csv_field(&group.name),
+                    group.file_count,
+                    group.parse_failures,
+                    group.analysis_failures,
+                    group.skipped,
+                    group.line_count,
+                    group.average_fta_score,
+                    group.weighted_fta_score,
+                    csv_field(&group.worst_files.join(" "))
+                ));
+            }
+        }
+        Some("markdown") => {
+            output.push_str(&format!(
+                "| {} | Num. files | Num. lines | Average FTA Score | Weighted FTA Score | Worst files |",
+                group_label
+            ));
+            output.push_str("\n| --- | --- | ---: | ---: | ---: | --- |");
+            for group in groups {
+                output.push_str(&format!(
+                    "\n| {} | {} | {} | {:.2} | {:.2} | {} |",
+                    markdown_cell(&group.name),
+                    group_file_count(group),
+                    group.line_count,
+                    group.average_fta_score,
+                    group.weighted_fta_score,
+                    markdown_cell(&group.worst_files.join(", "))
+                ));
+            }
+        }
+        Some("table") => {
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL);
+            table.set_content_arrangement(comfy_table::ContentArrangement::Dynamic);
+            table.set_header(vec![
+                group_label,
+                "Num. files",
+                "Num. lines",
+                "Average FTA Score",
+                "Weighted FTA Score",
+                "Worst files",
+            ]);
+
+            for group in groups {
+                if table.row_iter().count() >= output_limit {
+                    continue;
+                }
+                table.add_row(vec![
+                    truncate_string(&group.name, 50),
+                    group_file_count(group),
+                    group.line_count.to_string(),
+                    format!("{:.2}", group.average_fta_score),
+                    format!("{:.2}", group.weighted_fta_score),
+                    group
+                        .worst_files
+                        .iter()
+                        .map(|file_name| truncate_string(file_name, 50))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
                 ]);
             }
 
             output = format!(
-                "{}\n{} files analyzed in {}s.",
-                table.to_string(),
-                file_data_list.len(),
+                "{}\n{} groups analyzed in {}s.",
+                table,
+                groups.len(),
                 (elapsed * 10000.0).round() / 10000.0
             );
         }
@@ -1,7 +1,10 @@
 #[cfg(test)]
 mod tests {
-    use crate::output::{generate_output, truncate_string};
-    use crate::structs::{FileData, HalsteadMetrics};
+    use crate::output::{generate_group_output, generate_output, truncate_string};
+    use crate::structs::{
+        FileData, GroupSummary, HalsteadMetrics, Language, LineCounts, ParseFailure, SourceType,
+        Suppression, SuppressionKind,
+    };
 
     fn get_test_data() -> Vec<FileData> {
         vec![
@@ -22,8 +25,18 @@ mod tests {
                     bugs: 11.0,
                 },
                 line_count: 1,
+                lines: LineCounts::default(),
                 fta_score: 45.00,
                 assessment: "OK".to_string(),
+                package: None,
+                owners: vec![],
+                suppressions: vec![],
+                gate_fta_score: None,
+                syntax: Language::JavaScript,
+                source_type: SourceType::Module,
+                parse_failure: None,
+                skipped: None,
+                analysis_failure: None,
             },
             FileData {
                 file_name: "foo.tsx".to_string(),
@@ -42,8 +55,18 @@ mod tests {
                     bugs: 11.0,
                 },
                 line_count: 25,
+                lines: LineCounts::default(),
                 fta_score: 95.00,
                 assessment: "OK".to_string(),
+                package: None,
+                owners: vec![],
+                suppressions: vec![],
+                gate_fta_score: None,
+                syntax: Language::Tsx,
+                source_type: SourceType::Module,
+                parse_failure: None,
+                skipped: None,
+                analysis_failure: None,
             },
             FileData {
                 file_name: "bar.jsx".to_string(),
@@ -62,8 +85,18 @@ mod tests {
                     bugs: 11.0,
                 },
                 line_count: 50,
+                lines: LineCounts::default(),
                 fta_score: 145.00,
                 assessment: "OK".to_string(),
+                package: None,
+                owners: vec![],
+                suppressions: vec![],
+                gate_fta_score: None,
+                syntax: Language::Jsx,
+                source_type: SourceType::Module,
+                parse_failure: None,
+                skipped: None,
+                analysis_failure: None,
             },
         ]
     }
@@ -132,6 +165,26 @@ mod tests {
         assert_eq!(output_str, expected_output);
     }
 
+    #[test]
+    fn test_output_markdown_format() {
+        let mut file_data_list = get_test_data();
+        file_data_list[0].file_name = "a|b.js".to_string();
+        file_data_list[2].skipped = Some("too large".to_string());
+        let output_str = format!(
+            "\n{}\n",
+            generate_output(&file_data_list, "markdown".to_string(), &0.1_f64, 1)
+        );
+        let expected_output_raw = r##"
+            | File | Num. lines | FTA Score (Lower is better) | Assessment |
+            | --- | ---: | ---: | --- |
+            | a\|b.js | 1 | 45.00 | OK |
+            | foo.tsx | 25 | 95.00 | OK |
+            | bar.jsx | 50 |  | Skipped: too large |
+        "##;
+        let expected_output = format_expected_output(expected_output_raw);
+        assert_eq!(output_str, expected_output);
+    }
+
     #[test]
     fn test_output_table_format() {
         let file_data_list = get_test_data();
@@ -178,6 +231,111 @@ mod tests {
         assert_eq!(output_str, expected_output);
     }
 
+    #[test]
+    fn test_output_table_marks_suppressed_files() {
+        let mut file_data_list = get_test_data();
+        file_data_list[0].suppressions = vec![Suppression {
+            kind: SuppressionKind::File,
+            rules: vec![],
+            line: 1,
+            reason: Some("vendored".to_string()),
+        }];
+        let output_str = generate_output(&file_data_list, "table".to_string(), &0.1_f64, 1);
+        let expected_output_raw = r##"
+            ┌─────────┬────────────┬─────────────────────────────┬───────────────────────────┐
+            │ File    ┆ Num. lines ┆ FTA Score (Lower is better) ┆ Assessment                │
+            ╞═════════╪════════════╪═════════════════════════════╪═══════════════════════════╡
+            │ test.js ┆ 1          ┆ 45.00                       ┆ OK (suppressed: vendored) │
+            └─────────┴────────────┴─────────────────────────────┴───────────────────────────┘
+            3 files analyzed in 0.1s.
+        "##;
+
+        let expected_output = format_expected_output(expected_output_raw);
+        let expected_output = expected_output
+            .trim_start_matches('\n')
+            .trim_end_matches('\n');
+        assert_eq!(output_str, expected_output);
+
+        // CSV keeps the plain assessment
+        let output_str = generate_output(&file_data_list, "csv".to_string(), &0.1_f64, 1);
+        assert!(output_str.contains("test.js,1,45.00,OK\n"));
+    }
+
+    #[test]
+    fn test_output_reports_parse_failures() {
+        let mut file_data_list = get_test_data();
+        file_data_list[1].fta_score = 0.0;
+        file_data_list[1].assessment = "Failed to parse".to_string();
+        file_data_list[1].parse_failure = Some(ParseFailure {
+            message: "Expected ',', got ')'".to_string(),
+            line: 3,
+            column: 7,
+        });
+
+        let output_str = generate_output(&file_data_list, "table".to_string(), &0.1_f64, 100);
+        assert!(!output_str.contains("│ foo.tsx"));
+        assert!(output_str.ends_with(
+            "1 files failed to parse:\n  foo.tsx: Failed to parse at 3:7: Expected ',', got ')'\n2 files analyzed in 0.1s. 1 files failed to parse."
+        ));
+
+        let output_str = generate_output(&file_data_list, "csv".to_string(), &0.1_f64, 100);
+        assert!(output_str
+            .contains("\nfoo.tsx,25,,\"Failed to parse at 3:7: Expected ',', got ')'\"\n"));
+
+        let output_str = generate_output(&file_data_list, "json".to_string(), &0.1_f64, 100);
+        let json: serde_json::Value = serde_json::from_str(&output_str).unwrap();
+        assert_eq!(json[0].get("parse_failure"), None);
+        assert_eq!(json[1]["parse_failure"]["line"], 3);
+        assert_eq!(json[1]["parse_failure"]["column"], 7);
+    }
+
+    #[test]
+    fn test_output_lists_skipped_files() {
+        let mut file_data_list = get_test_data();
+        file_data_list[1].fta_score = 0.0;
+        file_data_list[1].assessment = "Skipped".to_string();
+        file_data_list[1].skipped = Some("not valid UTF-8".to_string());
+
+        let output_str = generate_output(&file_data_list, "table".to_string(), &0.1_f64, 100);
+        assert!(!output_str.contains("│ foo.tsx"));
+        assert!(output_str.ends_with(
+            "1 files skipped:\n  foo.tsx: not valid UTF-8\n2 files analyzed in 0.1s. 1 files skipped."
+        ));
+
+        let output_str = generate_output(&file_data_list, "csv".to_string(), &0.1_f64, 100);
+        assert!(output_str.contains("\nfoo.tsx,25,,Skipped: not valid UTF-8\n"));
+
+        let output_str = generate_output(&file_data_list, "json".to_string(), &0.1_f64, 100);
+        let json: serde_json::Value = serde_json::from_str(&output_str).unwrap();
+        assert_eq!(json[0].get("skipped"), None);
+        assert_eq!(json[1]["skipped"], "not valid UTF-8");
+    }
+
+    #[test]
+    fn test_output_lists_files_that_failed_to_analyze() {
+        let mut file_data_list = get_test_data();
+        file_data_list[1].fta_score = 0.0;
+        file_data_list[1].assessment = "Failed to analyze".to_string();
+        file_data_list[1].analysis_failure = Some("took longer than 100ms".to_string());
+
+        let output_str = generate_output(&file_data_list, "table".to_string(), &0.1_f64, 100);
+        assert!(output_str.ends_with(
+            "1 files failed to analyze:\n  foo.tsx: took longer than 100ms\n2 files analyzed in 0.1s. 1 files failed to analyze."
+        ));
+
+        let output_str = generate_output(&file_data_list, "csv".to_string(), &0.1_f64, 100);
+        assert!(output_str.contains("\nfoo.tsx,25,,Failed to analyze: took longer than 100ms\n"));
+    }
+
+    #[test]
+    fn test_output_csv_quotes_file_names() {
+        let mut file_data_list = get_test_data();
+        file_data_list[1].file_name = "src/a,\"b\".ts".to_string();
+
+        let output_str = generate_output(&file_data_list, "csv".to_string(), &0.1_f64, 100);
+        assert!(output_str.contains("\n\"src/a,\"\"b\"\".ts\",25,"));
+    }
+
     #[test]
     fn test_output_unspecified_format() {
         let file_data_list = get_test_data();
@@ -210,8 +368,18 @@ mod tests {
                     "bugs": 11.0
                 },
                 "line_count": 1,
+                "lines": {
+                    "physical": 0,
+                    "source": 0,
+                    "comment": 0,
+                    "blank": 0,
+                    "mixed": 0,
+                    "logical": 0
+                },
                 "fta_score": 45.0,
-                "assessment": "OK"
+                "assessment": "OK",
+                "syntax": "javascript",
+                "source_type": "module"
             },
             {
                 "file_name": "foo.tsx",
@@ -231,8 +399,18 @@ mod tests {
                     "bugs": 11.0
                 },
                 "line_count": 25,
+                "lines": {
+                    "physical": 0,
+                    "source": 0,
+                    "comment": 0,
+                    "blank": 0,
+                    "mixed": 0,
+                    "logical": 0
+                },
                 "fta_score": 95.0,
-                "assessment": "OK"
+                "assessment": "OK",
+                "syntax": "tsx",
+                "source_type": "module"
             },
             {
                 "file_name": "bar.jsx",
@@ -252,8 +430,146 @@ mod tests {
                     "bugs": 11.0
                 },
                 "line_count": 50,
+                "lines": {
+                    "physical": 0,
+                    "source": 0,
+                    "comment": 0,
+                    "blank": 0,
+                    "mixed": 0,
+                    "logical": 0
+                },
                 "fta_score": 145.0,
-                "assessment": "OK"
+                "assessment": "OK",
+                "syntax": "jsx",
+                "source_type": "module"
+            }
+        ]"##;
+
+        assert_eq!(
+            format_json_output(&output_str),
+            format_json_output(expected_output)
+        );
+    }
+
+    fn get_group_test_data() -> Vec<GroupSummary> {
+        vec![
+            GroupSummary {
+                name: "@scope/a".to_string(),
+                file_count: 2,
+                line_count: 75,
+                average_fta_score: 120.0,
+                weighted_fta_score: 128.333,
+                worst_files: vec!["bar.jsx".to_string(), "foo.tsx".to_string()],
+                parse_failures: 0,
+                analysis_failures: 0,
+                skipped: 0,
+            },
+            GroupSummary {
+                name: "b".to_string(),
+                file_count: 1,
+                line_count: 1,
+                average_fta_score: 45.0,
+                weighted_fta_score: 45.0,
+                worst_files: vec!["test.js".to_string()],
+                parse_failures: 0,
+                analysis_failures: 0,
+                skipped: 0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_group_output_csv_format() {
+        let mut groups = get_group_test_data();
+        groups[1].parse_failures = 2;
+        groups[1].analysis_failures = 1;
+        groups[1].skipped = 3;
+        let output_str = format!(
+            "\n{}\n",
+            generate_group_output(&groups, "Package", "csv".to_string(), &0.1_f64, 100)
+        );
+        let expected_output_raw = r##"
+            Package,Num. files,Parse failures,Analysis failures,Skipped,Num. lines,Average FTA Score,Weighted FTA Score,Worst files
+            @scope/a,2,0,0,0,75,120.00,128.33,bar.jsx foo.tsx
+            b,1,2,1,3,1,45.00,45.00,test.js
+        "##;
+        let expected_output = format_expected_output(expected_output_raw);
+        assert_eq!(output_str, expected_output);
+
+        groups[1].worst_files = vec!["a,b.ts".to_string(), "c.ts".to_string()];
+        let output_str =
+            generate_group_output(&groups, "Package", "csv".to_string(), &0.1_f64, 100);
+        assert!(output_str.ends_with("\nb,1,2,1,3,1,45.00,45.00,\"a,b.ts c.ts\""));
+    }
+
+    #[test]
+    fn test_group_output_table_can_be_limited() {
+        let groups = get_group_test_data();
+        let output_str =
+            generate_group_output(&groups, "Package", "table".to_string(), &0.1_f64, 1);
+
+        assert!(output_str.contains("@scope/a"));
+        assert!(!output_str.contains("test.js"));
+        assert!(output_str.ends_with("2 groups analyzed in 0.1s."));
+    }
+
+    #[test]
+    fn test_group_output_table_counts_parse_failures() {
+        let mut groups = get_group_test_data();
+        groups[1].parse_failures = 2;
+        let output_str =
+            generate_group_output(&groups, "Package", "table".to_string(), &0.1_f64, 100);
+
+        assert!(output_str.contains("1 (2 failed to parse)"));
+
+        groups[1].analysis_failures = 1;
+        groups[1].skipped = 3;
+        let output_str =
+            generate_group_output(&groups, "Package", "table".to_string(), &0.1_f64, 100);
+
+        assert!(output_str.contains("1 (2 failed to parse, 1 failed to analyze, 3 skipped)"));
+    }
+
+    #[test]
+    fn test_group_output_markdown_format() {
+        let mut groups = get_group_test_data();
+        groups[1].skipped = 1;
+        let output_str = format!(
+            "\n{}\n",
+            generate_group_output(&groups, "Owner", "markdown".to_string(), &0.1_f64, 1)
+        );
+        let expected_output_raw = r##"
+            | Owner | Num. files | Num. lines | Average FTA Score | Weighted FTA Score | Worst files |
+            | --- | --- | ---: | ---: | ---: | --- |
+            | @scope/a | 2 | 75 | 120.00 | 128.33 | bar.jsx, foo.tsx |
+            | b | 1 (1 skipped) | 1 | 45.00 | 45.00 | test.js |
+        "##;
+        let expected_output = format_expected_output(expected_output_raw);
+        assert_eq!(output_str, expected_output);
+    }
+
+    #[test]
+    fn test_group_output_json_format() {
+        let groups = get_group_test_data();
+        let output_str =
+            generate_group_output(&groups, "Package", "json".to_string(), &0.1_f64, 100);
+
+        let expected_output = r##"[
+            {
+                "name": "@scope/a",
+                "file_count": 2,
+                "line_count": 75,
+                "average_fta_score": 120.0,
+                "weighted_fta_score": 128.333,
+                "worst_files": ["bar.jsx", "foo.tsx"]
+            },
+            {
+                "name": "b",
+                "file_count": 1,
+                "line_count": 1,
+                "average_fta_score": 45.0,
+                "weighted_fta_score": 45.0,
+                "worst_files": ["test.js"]
             }
         ]"##;
 
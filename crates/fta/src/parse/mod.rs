@@ -1,11 +1,16 @@
-use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::PathBuf;
 
+use crate::lines::{classify, count_lines, logical_lines, LineKind};
+use crate::structs::{Language, LineCounts, ParseFailure, ParserOptions, SourceType};
+use crate::suppress::{parse_directive, Directive};
 use swc_common::comments::Comment;
 use swc_common::sync::Lrc;
 use swc_common::{comments::Comments, input::SourceFileInput};
-use swc_common::{BytePos, SourceMap};
-use swc_ecma_ast::{EsVersion, Module};
-use swc_ecma_parser::{error::Error, lexer::Lexer, Parser, Syntax, TsConfig};
+use swc_common::{BytePos, SourceMap, Spanned};
+use swc_ecma_ast::{EsVersion, Module, ModuleItem, Program, Script};
+use swc_ecma_parser::{error::Error, lexer::Lexer, EsConfig, Parser, Syntax, TsConfig};
 
 mod tests;
 
@@ -14,66 +19,256 @@ pub fn parse_module(
     use_tsx: bool,
     include_comments: bool,
 ) -> (Result<Module, Error>, usize) {
+    let dialect = Dialect {
+        language: Language::TypeScript.with_jsx(use_tsx),
+        source_type: Some(SourceType::Module),
+    };
+    let parsed = parse_source(
+        "input.ts",
+        source,
+        dialect,
+        include_comments,
+        &ParserOptions::default(),
+    );
+
+    (parsed.module, parsed.line_count)
+}
+
+/// How a file is parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dialect {
+    pub language: Language,
+    /// `None` when the file is a module only if it uses `import` or `export`
+    pub source_type: Option<SourceType>,
+}
+
+/// Picks the dialect of a file from its extension. TypeScript files are always modules, while
+/// .cjs files are CommonJS scripts and .js files are detected from their syntax. Files without a
+/// known extension are parsed as TypeScript.
+pub fn dialect_for(file_name: &str, options: &ParserOptions) -> Dialect {
+    let extension = file_name.rsplit_once('.').map(|(_, extension)| extension);
+    // Like TypeScript, the `jsx` parser option only applies to JavaScript files
+    let javascript = Language::JavaScript.with_jsx(options.jsx == Some(true));
+
+    let (language, source_type) = match extension {
+        Some("tsx") => (Language::Tsx, Some(SourceType::Module)),
+        Some("jsx") => (Language::Jsx, None),
+        Some("js") => (javascript, None),
+        Some("mjs") => (javascript, Some(SourceType::Module)),
+        Some("cjs") => (javascript, Some(SourceType::Script)),
+        _ => (Language::TypeScript, Some(SourceType::Module)),
+    };
+
+    Dialect {
+        language,
+        source_type,
+    }
+}
+
+fn script_to_module(script: Script) -> Module {
+    Module {
+        span: script.span,
+        body: script.body.into_iter().map(ModuleItem::Stmt).collect(),
+        shebang: script.shebang,
+    }
+}
+
+/// The ECMAScript version named by a tsconfig.json `target`, e.g. "ES2022" or "esnext"
+pub fn es_version(target: &str) -> Option<EsVersion> {
+    let version = match target.to_ascii_lowercase().as_str() {
+        "es3" => EsVersion::Es3,
+        "es5" => EsVersion::Es5,
+        "es6" | "es2015" => EsVersion::Es2015,
+        "es2016" => EsVersion::Es2016,
+        "es2017" => EsVersion::Es2017,
+        "es2018" => EsVersion::Es2018,
+        "es2019" => EsVersion::Es2019,
+        "es2020" => EsVersion::Es2020,
+        "es2021" => EsVersion::Es2021,
+        "es2022" => EsVersion::Es2022,
+        // Versions newer than the parser knows are parsed as the latest one
+        "esnext" | "es2023" | "es2024" | "es2025" => EsVersion::EsNext,
+        _ => return None,
+    };
+
+    Some(version)
+}
+
+/// The outcome of parsing a file, along with what's needed to relate the AST back to its lines.
+/// Spans of the AST point into the source as it was given, registered under its file name.
+pub struct ParsedSource {
+    pub module: Result<Module, Error>,
+    /// Lines with code, along with comment-only lines when comments are included
+    pub line_count: usize,
+    pub lines: LineCounts,
+    /// Suppression comments
+    pub directives: Vec<Directive>,
+    /// Lines that are empty or contain only whitespace
+    pub blank_lines: HashSet<usize>,
+    /// Comment-only lines that aren't counted in `line_count`
+    pub comment_lines: HashSet<usize>,
+    pub source_map: Lrc<SourceMap>,
+    /// Whether the file was parsed as a module or a script, detected when the dialect leaves it open
+    pub source_type: SourceType,
+}
+
+impl ParsedSource {
+    /// Describes a syntax error and where it is
+    pub fn failure(&self, error: &Error) -> ParseFailure {
+        let position = self.source_map.lookup_char_pos(error.span().lo);
+
+        ParseFailure {
+            message: error.kind().msg().to_string(),
+            line: position.line,
+            column: position.col_display + 1,
+        }
+    }
+}
+
+pub fn parse_source(
+    file_name: &str,
+    source: &str,
+    dialect: Dialect,
+    include_comments: bool,
+    options: &ParserOptions,
+) -> ParsedSource {
     let cm: Lrc<SourceMap> = Default::default();
     let comments = CountingComments::new();
-    let code: String = source
-        .lines()
-        .filter(|line| !line.trim().is_empty()) // Remove lines that are empty or contain only whitespace
-        .collect::<Vec<_>>()
-        .join("\n");
-
     let fm = cm.new_source_file(
-        swc_common::FileName::Custom("input.ts".to_string()),
-        code.clone(),
+        swc_common::FileName::Real(PathBuf::from(file_name)),
+        source.to_string(),
     );
 
-    let ts_config = TsConfig {
-        tsx: use_tsx,
-        decorators: false,
-        dts: false,
-        no_early_errors: false,
-        disallow_ambiguous_jsx_like: true,
+    let decorators = options.decorators.unwrap_or(false);
+    let syntax = match dialect.language {
+        Language::TypeScript | Language::Tsx => Syntax::Typescript(TsConfig {
+            tsx: dialect.language.is_jsx(),
+            decorators,
+            dts: false,
+            no_early_errors: false,
+            disallow_ambiguous_jsx_like: true,
+        }),
+        Language::JavaScript | Language::Jsx => Syntax::Es(EsConfig {
+            jsx: dialect.language.is_jsx(),
+            decorators,
+            // CommonJS modules are wrapped in a function, so they may return early
+            allow_return_outside_function: dialect.source_type != Some(SourceType::Module),
+            ..Default::default()
+        }),
     };
 
     let lexer = Lexer::new(
-        Syntax::Typescript(ts_config),
-        EsVersion::Es2020,
+        syntax,
+        options
+            .target
+            .as_deref()
+            .and_then(es_version)
+            .unwrap_or(EsVersion::Es2020),
         SourceFileInput::from(&*fm),
         Some(&comments),
     );
 
     let mut parser = Parser::new_from(lexer);
-    let parsed = parser.parse_module();
+    let parsed = match dialect.source_type {
+        Some(SourceType::Module) => parser
+            .parse_module()
+            .map(|module| (module, SourceType::Module)),
+        Some(SourceType::Script) => parser
+            .parse_script()
+            .map(|script| (script_to_module(script), SourceType::Script)),
+        None => parser.parse_program().map(|program| match program {
+            Program::Module(module) => (module, SourceType::Module),
+            Program::Script(script) => (script_to_module(script), SourceType::Script),
+        }),
+    };
+    let source_type = match &parsed {
+        Ok((_, source_type)) => *source_type,
+        Err(_) => dialect.source_type.unwrap_or(SourceType::Module),
+    };
 
-    let mut line_count = code.lines().count();
-    if include_comments == false {
-        line_count -= comments.count()
+    let start = fm.start_pos.0;
+    let comment_ranges: Vec<(usize, usize)> = comments
+        .spans
+        .take()
+        .into_iter()
+        .map(|(lo, hi)| ((lo.0 - start) as usize, (hi.0 - start) as usize))
+        .collect();
+    let kinds = classify(source, &comment_ranges);
+    let lines_of = |kind: LineKind| -> HashSet<usize> {
+        (1..=kinds.len())
+            .filter(|line| kinds[line - 1] == kind)
+            .collect()
     };
+    let blank_lines = lines_of(LineKind::Blank);
+    let logical = parsed
+        .as_ref()
+        .map(|(module, _)| logical_lines(module))
+        .unwrap_or(0);
+    let lines = count_lines(&kinds, logical);
 
-    (parsed, line_count)
+    let (line_count, comment_lines) = if include_comments {
+        (lines.source + lines.comment, HashSet::new())
+    } else {
+        (lines.source, lines_of(LineKind::Comment))
+    };
+    let line_of = |pos: BytePos| cm.lookup_char_pos(pos).line;
+
+    // `fta-ignore-next-*` directives apply to the next line that isn't blank
+    let next_line = |line: usize| {
+        (line + 1..=kinds.len())
+            .find(|line| !blank_lines.contains(line))
+            .unwrap_or(line + 1)
+    };
+    let directives = comments
+        .directives
+        .take()
+        .into_iter()
+        // A directive spanning several lines applies from its last one
+        .filter_map(|(text, span_hi)| {
+            let line = line_of(span_hi);
+            parse_directive(&text, line, next_line(line))
+        })
+        .collect();
+
+    ParsedSource {
+        module: parsed.map(|(module, _)| module),
+        line_count,
+        lines,
+        directives,
+        blank_lines,
+        comment_lines,
+        source_map: cm,
+        source_type,
+    }
 }
 
+/// Collects the spans of comments, which tell comment lines from code, and `fta-` suppression
+/// comments
 struct CountingComments {
-    count: Cell<usize>,
+    spans: RefCell<Vec<(BytePos, BytePos)>>,
+    directives: RefCell<Vec<(String, BytePos)>>,
 }
 
 impl Comments for CountingComments {
-    fn add_leading(self: &CountingComments, _pos: BytePos, _comment: Comment) {
-        self.count
-            .set(self.count.get() + 1 + _comment.text.matches('\n').count());
+    fn add_leading(self: &CountingComments, _pos: BytePos, comment: Comment) {
+        self.collect(&comment);
     }
 
-    fn add_leading_comments(self: &CountingComments, _pos: BytePos, _comments: Vec<Comment>) {
-        let comment_count: usize = _comments
-            .iter()
-            .map(|comment| comment.text.matches('\n').count())
-            .sum();
-        self.count.set(self.count.get() + 1 + comment_count);
+    fn add_leading_comments(self: &CountingComments, _pos: BytePos, comments: Vec<Comment>) {
+        for comment in &comments {
+            self.collect(comment);
+        }
     }
 
-    fn add_trailing(self: &CountingComments, _pos: BytePos, _comment: Comment) {}
+    fn add_trailing(self: &CountingComments, _pos: BytePos, comment: Comment) {
+        self.collect(&comment);
+    }
 
-    fn add_trailing_comments(self: &CountingComments, _pos: BytePos, _comments: Vec<Comment>) {}
+    fn add_trailing_comments(self: &CountingComments, _pos: BytePos, comments: Vec<Comment>) {
+        for comment in &comments {
+            self.collect(comment);
+        }
+    }
 
     fn has_leading(&self, _pos: BytePos) -> bool {
         false
@@ -91,9 +286,7 @@ impl Comments for CountingComments {
         None
     }
 
-    fn move_leading(&self, _from: swc_common::BytePos, _to: swc_common::BytePos) {
-        ()
-    }
+    fn move_leading(&self, _from: swc_common::BytePos, _to: swc_common::BytePos) {}
 
     fn get_leading(&self, _pos: swc_common::BytePos) -> Option<Vec<swc_common::comments::Comment>> {
         None
@@ -114,11 +307,19 @@ impl Comments for CountingComments {
 impl CountingComments {
     fn new() -> Self {
         Self {
-            count: Cell::new(0),
+            spans: RefCell::new(Vec::new()),
+            directives: RefCell::new(Vec::new()),
         }
     }
 
-    fn count(&self) -> usize {
-        self.count.get()
+    fn collect(&self, comment: &Comment) {
+        self.spans
+            .borrow_mut()
+            .push((comment.span.lo, comment.span.hi));
+        if comment.text.trim_start().starts_with("fta-") {
+            self.directives
+                .borrow_mut()
+                .push((comment.text.to_string(), comment.span.hi));
+        }
     }
 }
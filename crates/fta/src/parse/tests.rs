@@ -1,6 +1,11 @@
 #[cfg(test)]
 mod tests {
-    use crate::parse::parse_module;
+    use crate::parse::{dialect_for, parse_module, parse_source, Dialect};
+    use crate::structs::{Language, ParserOptions, SourceType};
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+    use swc_common::{FileName, Spanned};
+    use swc_ecma_ast::{Expr, ModuleItem, Stmt};
 
     #[test]
     fn test_parse_module() {
@@ -66,4 +71,123 @@ mod tests {
         assert!(parsed_module.is_ok(), "Failed to parse TypeScript code");
         assert_eq!(line_count, 10, "Incorrect line count");
     }
+
+    #[test]
+    fn test_spans_point_to_the_original_source() {
+        let source = "\n/*\n\n  Licensed under MIT\n*/\nconst a = 1;\n\n\nfunction add() {}\n";
+        let parsed = parse_source(
+            "src/math.ts",
+            source,
+            dialect_for("src/math.ts", &ParserOptions::default()),
+            false,
+            &ParserOptions::default(),
+        );
+        let module = parsed.module.unwrap();
+
+        let position = parsed.source_map.lookup_char_pos(module.body[1].span().lo);
+        assert_eq!(position.line, 9);
+        assert_eq!(
+            position.file.name,
+            FileName::Real(PathBuf::from("src/math.ts"))
+        );
+        assert_eq!(parsed.blank_lines, HashSet::from([1, 3, 7, 8]));
+        assert_eq!(parsed.comment_lines, HashSet::from([2, 4, 5]));
+        assert_eq!(parsed.line_count, 2);
+    }
+
+    #[test]
+    fn test_dialect_for() {
+        let options = ParserOptions::default();
+        let dialect = |file_name: &str| {
+            let dialect = dialect_for(file_name, &options);
+            (dialect.language, dialect.source_type)
+        };
+
+        assert_eq!(
+            dialect("a.mts"),
+            (Language::TypeScript, Some(SourceType::Module))
+        );
+        assert_eq!(
+            dialect("a.cts"),
+            (Language::TypeScript, Some(SourceType::Module))
+        );
+        assert_eq!(dialect("a.tsx"), (Language::Tsx, Some(SourceType::Module)));
+        assert_eq!(dialect("a.js"), (Language::JavaScript, None));
+        assert_eq!(
+            dialect("a.mjs"),
+            (Language::JavaScript, Some(SourceType::Module))
+        );
+        assert_eq!(
+            dialect("a.cjs"),
+            (Language::JavaScript, Some(SourceType::Script))
+        );
+        assert_eq!(dialect("a.jsx"), (Language::Jsx, None));
+        assert_eq!(
+            dialect("stdin"),
+            (Language::TypeScript, Some(SourceType::Module))
+        );
+
+        let jsx = ParserOptions {
+            jsx: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(dialect_for("a.js", &jsx).language, Language::Jsx);
+        assert_eq!(dialect_for("a.ts", &jsx).language, Language::TypeScript);
+    }
+
+    #[test]
+    fn test_javascript_is_not_parsed_as_typescript() {
+        let parse_expression = |file_name: &str| {
+            let dialect = dialect_for(file_name, &ParserOptions::default());
+            let parsed = parse_source(
+                file_name,
+                "a < b > (c);",
+                dialect,
+                false,
+                &ParserOptions::default(),
+            );
+            match &parsed.module.unwrap().body[0] {
+                ModuleItem::Stmt(Stmt::Expr(statement)) => *statement.expr.clone(),
+                item => panic!("Unexpected item {:?}", item),
+            }
+        };
+
+        // Two comparisons in JavaScript, a call with a type argument in TypeScript
+        assert!(matches!(parse_expression("a.js"), Expr::Bin(_)));
+        assert!(matches!(parse_expression("a.ts"), Expr::Call(_)));
+    }
+
+    #[test]
+    fn test_scripts_are_detected() {
+        let parse = |file_name: &str, source: &str| {
+            let dialect = dialect_for(file_name, &ParserOptions::default());
+            let parsed = parse_source(file_name, source, dialect, false, &ParserOptions::default());
+            parsed.module.map(|_| parsed.source_type).ok()
+        };
+        let common_js = r#"
+            const legacy = 010;
+            if (!module.parent) return;
+            module.exports = legacy;
+        "#;
+
+        assert_eq!(parse("a.cjs", common_js), Some(SourceType::Script));
+        assert_eq!(parse("a.js", common_js), Some(SourceType::Script));
+        assert_eq!(
+            parse("a.js", "import a from 'a';\nexport default a;"),
+            Some(SourceType::Module)
+        );
+
+        let dialect = Dialect {
+            language: Language::JavaScript,
+            source_type: Some(SourceType::Script),
+        };
+        let parsed = parse_source(
+            "a.js",
+            "with (a) { b(); }",
+            dialect,
+            false,
+            &ParserOptions::default(),
+        );
+        assert!(parsed.module.is_ok());
+    }
 }
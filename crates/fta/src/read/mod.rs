@@ -0,0 +1,113 @@
+use crate::structs::{Encoding, FtaConfigResolved};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+mod tests;
+
+/// Why the source of a file couldn't be read
+#[derive(Debug)]
+pub enum ReadError {
+    /// The file is larger than `max_file_size`
+    TooLarge {
+        size: u64,
+        limit: usize,
+    },
+    Io(io::Error),
+    /// The file isn't valid UTF-8 and no fallback `encoding` is set
+    InvalidUtf8,
+    /// The file has a UTF-16 byte order mark but isn't valid UTF-16
+    InvalidUtf16,
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::TooLarge { size, limit } => write!(
+                f,
+                "{} bytes is larger than max_file_size ({} bytes)",
+                size, limit
+            ),
+            ReadError::Io(err) => write!(f, "unable to read: {}", err),
+            ReadError::InvalidUtf8 => write!(
+                f,
+                "not valid UTF-8, set `encoding` to read it as another encoding"
+            ),
+            ReadError::InvalidUtf16 => write!(f, "not valid UTF-16"),
+        }
+    }
+}
+
+/// Reads the source of a file, checking its size before reading it
+pub fn read_source(path: &Path, config: &FtaConfigResolved) -> Result<String, ReadError> {
+    let size = fs::metadata(path).map_err(ReadError::Io)?.len();
+    check_size(size, config)?;
+
+    let bytes = fs::read(path).map_err(ReadError::Io)?;
+    decode(bytes, config.encoding)
+}
+
+/// Fails for sources larger than `max_file_size`
+pub fn check_size(size: u64, config: &FtaConfigResolved) -> Result<(), ReadError> {
+    if size > config.max_file_size as u64 {
+        return Err(ReadError::TooLarge {
+            size,
+            limit: config.max_file_size,
+        });
+    }
+
+    Ok(())
+}
+
+/// Decodes a source, following its byte order mark if it has one and dropping it. Sources without
+/// one are read as UTF-8, falling back to `fallback` when they aren't valid UTF-8.
+pub fn decode(bytes: Vec<u8>, fallback: Encoding) -> Result<String, ReadError> {
+    if let Some(bytes) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return decode(bytes.to_vec(), fallback);
+    }
+    if let Some(bytes) = bytes.strip_prefix(b"\xFF\xFE") {
+        return decode_utf16(bytes, u16::from_le_bytes);
+    }
+    if let Some(bytes) = bytes.strip_prefix(b"\xFE\xFF") {
+        return decode_utf16(bytes, u16::from_be_bytes);
+    }
+
+    match String::from_utf8(bytes) {
+        Ok(source) => Ok(source),
+        Err(err) => {
+            let bytes = err.into_bytes();
+            match fallback {
+                Encoding::Utf8 => Err(ReadError::InvalidUtf8),
+                Encoding::Latin1 => Ok(bytes.iter().map(|&byte| byte as char).collect()),
+                Encoding::Windows1252 => Ok(bytes.iter().map(|&byte| windows_1252(byte)).collect()),
+            }
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> Result<String, ReadError> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(ReadError::InvalidUtf16);
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|chunk| to_unit([chunk[0], chunk[1]]))
+        .collect();
+
+    String::from_utf16(&units).map_err(|_| ReadError::InvalidUtf16)
+}
+
+/// Windows-1252 is Latin-1, except for the printable characters it puts in 0x80 to 0x9F
+fn windows_1252(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž',
+        '\u{8F}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}',
+        'ž', 'Ÿ',
+    ];
+
+    match byte {
+        0x80..=0x9F => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
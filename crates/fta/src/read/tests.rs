@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::get_default_config;
+    use crate::read::{check_size, decode, read_source, ReadError};
+    use crate::structs::Encoding;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_decode_utf8() {
+        let source = decode("const café = 1;".as_bytes().to_vec(), Encoding::Utf8).unwrap();
+        assert_eq!(source, "const café = 1;");
+
+        let source = decode(b"\xEF\xBB\xBFconst a = 1;".to_vec(), Encoding::Utf8).unwrap();
+        assert_eq!(source, "const a = 1;");
+    }
+
+    #[test]
+    fn test_decode_utf16_with_a_byte_order_mark() {
+        let units = "let é;".encode_utf16();
+        let little_endian: Vec<u8> = b"\xFF\xFE"
+            .iter()
+            .copied()
+            .chain(units.clone().flat_map(u16::to_le_bytes))
+            .collect();
+        let big_endian: Vec<u8> = b"\xFE\xFF"
+            .iter()
+            .copied()
+            .chain(units.flat_map(u16::to_be_bytes))
+            .collect();
+
+        assert_eq!(decode(little_endian, Encoding::Utf8).unwrap(), "let é;");
+        assert_eq!(decode(big_endian, Encoding::Utf8).unwrap(), "let é;");
+        assert!(matches!(
+            decode(b"\xFF\xFEa".to_vec(), Encoding::Utf8),
+            Err(ReadError::InvalidUtf16)
+        ));
+    }
+
+    #[test]
+    fn test_decode_falls_back_to_the_encoding() {
+        let bytes = b"// caf\xE9 \x80\nconst a = 1;".to_vec();
+
+        assert!(matches!(
+            decode(bytes.clone(), Encoding::Utf8),
+            Err(ReadError::InvalidUtf8)
+        ));
+        assert_eq!(
+            decode(bytes.clone(), Encoding::Latin1).unwrap(),
+            "// café \u{80}\nconst a = 1;"
+        );
+        assert_eq!(
+            decode(bytes, Encoding::Windows1252).unwrap(),
+            "// café €\nconst a = 1;"
+        );
+    }
+
+    #[test]
+    fn test_read_source_checks_the_size() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.ts");
+        fs::write(&path, "const a = 1;\n").unwrap();
+        let mut config = get_default_config();
+
+        assert_eq!(read_source(&path, &config).unwrap(), "const a = 1;\n");
+
+        config.max_file_size = 10;
+        let error = read_source(&path, &config).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "13 bytes is larger than max_file_size (10 bytes)"
+        );
+        assert!(check_size(10, &config).is_ok());
+
+        let error = read_source(&dir.path().join("missing.ts"), &config).unwrap_err();
+        assert!(matches!(error, ReadError::Io(_)));
+    }
+}
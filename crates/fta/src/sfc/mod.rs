@@ -0,0 +1,179 @@
+use crate::parse::Dialect;
+use crate::structs::{Language, SourceType};
+
+mod tests;
+
+/// Extensions of the single-file components whose scripts are extracted before parsing
+const COMPONENT_EXTENSIONS: [&str; 3] = [".vue", ".svelte", ".astro"];
+
+/// The scripts of a single-file component, as a single source the size of the component: everything
+/// outside of the scripts is blanked out, so that lines keep their numbers in the component.
+#[derive(Debug, PartialEq)]
+pub struct ExtractedScript {
+    pub source: String,
+    pub dialect: Dialect,
+}
+
+/// Whether the file is a single-file component, see `COMPONENT_EXTENSIONS`
+pub fn is_component(file_name: &str) -> bool {
+    COMPONENT_EXTENSIONS
+        .iter()
+        .any(|extension| file_name.ends_with(extension))
+}
+
+/// Extracts the `<script>` blocks of a Vue, Svelte or Astro component, along with the frontmatter
+/// of an Astro component. Returns `None` for other files.
+pub fn extract_script(file_name: &str, source: &str) -> Option<ExtractedScript> {
+    if !is_component(file_name) {
+        return None;
+    }
+
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let mut language = Language::JavaScript;
+
+    // Astro components start with a TypeScript frontmatter between `---` fences
+    let mut offset = 0;
+    if file_name.ends_with(".astro") {
+        language = Language::TypeScript;
+        if let Some((start, end)) = frontmatter(source) {
+            ranges.push((start, end));
+            offset = end;
+        }
+    }
+
+    while let Some((attributes, content_start)) = next_script_tag(source, offset) {
+        let content_end =
+            find_ignore_case(source, "</script", content_start).unwrap_or(source.len());
+        offset = content_end;
+
+        if !is_javascript(&attributes) {
+            continue;
+        }
+        match attribute(&attributes, "lang").as_deref() {
+            Some("ts") | Some("typescript") => {
+                language = Language::TypeScript.with_jsx(language.is_jsx())
+            }
+            Some("tsx") => language = Language::Tsx,
+            Some("jsx") => language = language.with_jsx(true),
+            _ => {}
+        }
+        ranges.push((content_start, content_end));
+    }
+
+    Some(ExtractedScript {
+        source: blank_outside(source, &ranges),
+        dialect: Dialect {
+            language,
+            source_type: Some(SourceType::Module),
+        },
+    })
+}
+
+/// The byte range of the content of an Astro frontmatter, if the component has one
+fn frontmatter(source: &str) -> Option<(usize, usize)> {
+    let trimmed = source.trim_start();
+    if !trimmed.starts_with("---") {
+        return None;
+    }
+
+    let start = source.len() - trimmed.len() + 3;
+    let end = source[start..]
+        .match_indices("---")
+        .map(|(index, _)| start + index)
+        .find(|&index| index == 0 || source[..index].ends_with('\n'))?;
+
+    Some((start, end))
+}
+
+/// Finds the next `<script ...>` tag from `offset`, skipping HTML comments. Returns its attributes
+/// and where its content starts.
+fn next_script_tag(source: &str, mut offset: usize) -> Option<(String, usize)> {
+    loop {
+        let comment = source[offset..].find("<!--").map(|index| offset + index);
+        let tag = find_ignore_case(source, "<script", offset)?;
+
+        if let Some(comment) = comment.filter(|&comment| comment < tag) {
+            offset = source[comment..]
+                .find("-->")
+                .map(|index| comment + index + 3)?;
+            continue;
+        }
+
+        let after_name = tag + "<script".len();
+        let is_script_tag = source[after_name..]
+            .chars()
+            .next()
+            .is_some_and(|c| c == '>' || c.is_whitespace());
+        let tag_end = source[after_name..]
+            .find('>')
+            .map(|index| after_name + index)?;
+        if !is_script_tag {
+            offset = tag_end;
+            continue;
+        }
+
+        let attributes = source[after_name..tag_end].to_string();
+        return Some((attributes, tag_end + 1));
+    }
+}
+
+fn find_ignore_case(source: &str, needle: &str, offset: usize) -> Option<usize> {
+    source.as_bytes()[offset..]
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+        .map(|index| offset + index)
+}
+
+/// The value of an attribute of a tag, e.g. `lang` in `setup lang="ts"`
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    let mut rest = attributes;
+    while let Some(index) = rest.find(name) {
+        let before = rest[..index].chars().last();
+        let after = rest[index + name.len()..].trim_start();
+        rest = &rest[index + name.len()..];
+
+        if before.is_some_and(|c| !c.is_whitespace()) {
+            continue;
+        }
+        let value = match after.strip_prefix('=') {
+            Some(value) => value.trim_start(),
+            None => continue,
+        };
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or_default(),
+            _ => value.split_whitespace().next().unwrap_or_default(),
+        };
+        return Some(value.trim_end_matches('/').to_ascii_lowercase());
+    }
+
+    None
+}
+
+/// Whether a script tag holds code rather than data, e.g. `type="application/ld+json"`
+fn is_javascript(attributes: &str) -> bool {
+    match attribute(attributes, "type").as_deref() {
+        None
+        | Some("")
+        | Some("module")
+        | Some("text/javascript")
+        | Some("application/javascript") => true,
+        Some(script_type) => script_type.ends_with("typescript") || script_type == "ts",
+    }
+}
+
+/// Replaces everything outside of `ranges` with spaces, keeping line breaks
+fn blank_outside(source: &str, ranges: &[(usize, usize)]) -> String {
+    source
+        .char_indices()
+        .map(|(index, c)| {
+            let inside = ranges
+                .iter()
+                .any(|&(start, end)| index >= start && index < end);
+            if inside || c == '\n' {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use crate::parse::Dialect;
+    use crate::sfc::{attribute, extract_script, ExtractedScript};
+    use crate::structs::{Language, SourceType};
+
+    fn module(language: Language) -> Dialect {
+        Dialect {
+            language,
+            source_type: Some(SourceType::Module),
+        }
+    }
+
+    /// The non-blank lines of an extracted script, with their line numbers in the component
+    fn script_lines(extracted: &ExtractedScript) -> Vec<(usize, &str)> {
+        extracted
+            .source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| (index + 1, line.trim()))
+            .collect()
+    }
+
+    #[test]
+    fn test_other_files_are_not_extracted() {
+        assert_eq!(extract_script("a.ts", "<script></script>"), None);
+        assert_eq!(extract_script("vue.js", "<script></script>"), None);
+    }
+
+    #[test]
+    fn test_vue_scripts_keep_their_lines() {
+        let source = r#"<template>
+  <div>{{ count }}</div>
+</template>
+
+<script lang="ts">
+export default { name: "Counter" };
+</script>
+
+<SCRIPT setup lang='TS'>
+const count: number = 1;
+</SCRIPT>
+
+<style>
+div { color: red; }
+</style>
+"#;
+
+        let extracted = extract_script("Counter.vue", source).unwrap();
+        assert_eq!(extracted.dialect, module(Language::TypeScript));
+        assert_eq!(extracted.source.lines().count(), source.lines().count());
+        assert_eq!(
+            script_lines(&extracted),
+            vec![
+                (6, r#"export default { name: "Counter" };"#),
+                (10, "const count: number = 1;"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_svelte_scripts_default_to_javascript() {
+        let source = r#"<script context="module">
+  export const preload = () => {};
+</script>
+<!-- <script lang="ts">let commented: string;</script> -->
+<script type="application/ld+json">{ "@type": "Person" }</script>
+<script>
+  let name = "world";
+</script>
+<h1>Hello {name}!</h1>
+"#;
+
+        let extracted = extract_script("App.svelte", source).unwrap();
+        assert_eq!(extracted.dialect, module(Language::JavaScript));
+        assert_eq!(
+            script_lines(&extracted),
+            vec![
+                (2, "export const preload = () => {};"),
+                (7, r#"let name = "world";"#),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_astro_frontmatter_is_typescript() {
+        let source = r#"---
+import Layout from "../layouts/Layout.astro";
+const title: string = "Home";
+---
+<Layout title={title}>
+  <h1>---</h1>
+</Layout>
+<script>
+  document.title = "Home";
+</script>
+"#;
+
+        let extracted = extract_script("index.astro", source).unwrap();
+        assert_eq!(extracted.dialect, module(Language::TypeScript));
+        assert_eq!(
+            script_lines(&extracted),
+            vec![
+                (2, r#"import Layout from "../layouts/Layout.astro";"#),
+                (3, r#"const title: string = "Home";"#),
+                (9, r#"document.title = "Home";"#),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_components_without_scripts_are_empty() {
+        let extracted = extract_script("Static.vue", "<template><p>Hi</p></template>\n").unwrap();
+        assert!(extracted.source.trim().is_empty());
+    }
+
+    #[test]
+    fn test_attribute() {
+        assert_eq!(
+            attribute(r#" setup lang="ts""#, "lang"),
+            Some("ts".to_string())
+        );
+        assert_eq!(
+            attribute(" lang=tsx async", "lang"),
+            Some("tsx".to_string())
+        );
+        assert_eq!(attribute(r#" xml:lang="en""#, "lang"), None);
+        assert_eq!(attribute(" setup", "lang"), None);
+    }
+}
@@ -1,17 +1,135 @@
-use serde::{Deserialize, Serialize};
+use globset::GlobSet;
+use ignore::gitignore::Gitignore;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::sync::OnceLock;
 
-#[derive(Debug, Deserialize, Default)]
+/// Accepts either a single string or a list of strings
+fn string_or_list<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrList {
+        String(String),
+        List(Vec<String>),
+    }
+
+    Ok(match Option::<StringOrList>::deserialize(deserializer)? {
+        Some(StringOrList::String(value)) => Some(vec![value]),
+        Some(StringOrList::List(values)) => Some(values),
+        None => None,
+    })
+}
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
 pub struct FtaConfigOptional {
+    /// Presets this config builds on, as paths relative to it or packages under node_modules
+    #[serde(
+        default,
+        deserialize_with = "string_or_list",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub extends: Option<Vec<String>>,
+    /// List options whose values replace the inherited ones instead of being added to them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replace: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub extensions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude_filenames: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude_directories: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub output_limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub score_cap: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub include_comments: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_lines: Option<ScoreLines>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude_under: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Encoding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_generated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<Vec<FtaConfigOverride>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parser: Option<ParserOptions>,
+}
+
+/// The lines the FTA score counts
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreLines {
+    /// Lines with code, along with comment-only lines when `include_comments` is set
+    #[default]
+    Source,
+    /// Every line of the file, including blank lines
+    Physical,
+    /// Statements and declarations, however they're laid out. Grouping parentheses are left out of
+    /// the score too, so that it doesn't change with the formatting.
+    Logical,
+}
+
+/// The encoding files are read as when they aren't valid UTF-8. Files with a byte order mark are
+/// read as the encoding it marks.
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Files that aren't valid UTF-8 are reported as unreadable
+    #[default]
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "latin1")]
+    Latin1,
+    #[serde(rename = "windows-1252")]
+    Windows1252,
+}
+
+/// Options of the parser. Unset options come from the nearest tsconfig.json or jsconfig.json.
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ParserOptions {
+    /// Whether decorators are allowed, like `experimentalDecorators`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decorators: Option<bool>,
+    /// Whether JSX is allowed in .js, .mjs and .cjs files, like setting `jsx`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jsx: Option<bool>,
+    /// The ECMAScript version to parse, like `target`, e.g. "es2022" or "esnext"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+impl ParserOptions {
+    /// Fills the options that aren't set from `lower`
+    pub fn or(self, lower: ParserOptions) -> ParserOptions {
+        ParserOptions {
+            decorators: self.decorators.or(lower.decorators),
+            jsx: self.jsx.or(lower.jsx),
+            target: self.target.or(lower.target),
+        }
+    }
+}
+
+/// Config that only applies to the files matching its selectors: `files` globs and/or owners
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+pub struct FtaConfigOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owners: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub config: FtaConfigOptional,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct FtaConfigResolved {
     pub extensions: Vec<String>,
     pub exclude_filenames: Vec<String>,
@@ -19,10 +137,36 @@ pub struct FtaConfigResolved {
     pub output_limit: usize,
     pub score_cap: usize,
     pub include_comments: bool,
+    pub score_lines: ScoreLines,
     pub exclude_under: usize,
+    /// Files larger than this many bytes are skipped
+    pub max_file_size: usize,
+    pub encoding: Encoding,
+    /// Analyze files that look generated or minified instead of skipping them
+    pub include_generated: bool,
+    /// How long the analysis of a single file may take, in milliseconds
+    pub file_timeout_ms: Option<u64>,
+    pub overrides: Vec<FtaConfigOverride>,
+    pub parser: ParserOptions,
+    /// Settings from FTA_* variables, `--set` and CLI flags, layered again on top of nested configs
+    /// and overrides so that they take precedence over every config file
+    #[serde(skip)]
+    pub command_line: FtaConfigOptional,
+    /// The patterns above compiled for matching, see `FtaConfigResolved::matchers`
+    #[serde(skip)]
+    pub(crate) matchers: OnceLock<ConfigMatchers>,
 }
 
-#[derive(Debug, Serialize, PartialEq)]
+/// The patterns of a resolved config, compiled the first time a file is matched against them
+#[derive(Debug, Clone)]
+pub struct ConfigMatchers {
+    pub exclude_filenames: GlobSet,
+    pub exclude_directories: Gitignore,
+    /// The `files` globs of each override, `None` for overrides without any
+    pub override_files: Vec<Option<GlobSet>>,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq)]
 pub struct HalsteadMetrics {
     pub uniq_operators: usize,  // number of unique operators
     pub uniq_operands: usize,   // number of unique operands
@@ -37,12 +181,178 @@ pub struct HalsteadMetrics {
     pub bugs: f64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct FileData {
     pub file_name: String,
     pub cyclo: usize,
     pub halstead: HalsteadMetrics,
+    /// The lines the score counts, see `score_lines`
     pub line_count: usize,
+    pub lines: LineCounts,
     pub fta_score: f64,
     pub assessment: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    /// Suppression comments found in the file, see `fta-ignore-file` and friends
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suppressions: Vec<Suppression>,
+    /// The score gates use when some lines or functions are suppressed, i.e. without them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gate_fta_score: Option<f64>,
+    /// The syntax the file was parsed with
+    pub syntax: Language,
+    pub source_type: SourceType,
+    /// Why the file couldn't be parsed, in which case it has no metrics
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_failure: Option<ParseFailure>,
+    /// Why the file wasn't analyzed: it couldn't be read, is larger than `max_file_size` or looks
+    /// generated or minified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+    /// Why the analysis of the file was cut short, when it panicked or took longer than
+    /// `file_timeout_ms`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analysis_failure: Option<String>,
+}
+
+impl FileData {
+    /// Whether the file was read, parsed and analyzed, i.e. it has metrics
+    pub fn is_analyzed(&self) -> bool {
+        self.parse_failure.is_none() && self.skipped.is_none() && self.analysis_failure.is_none()
+    }
+}
+
+/// How the lines of a file break down. Blank lines inside block comments count as blank.
+#[derive(Debug, Default, Serialize, Clone, PartialEq)]
+pub struct LineCounts {
+    pub physical: usize,
+    /// Lines with code, including those that also have a comment
+    pub source: usize,
+    /// Lines with comments and no code
+    pub comment: usize,
+    pub blank: usize,
+    /// Lines with both code and a comment
+    pub mixed: usize,
+    /// Statements and declarations
+    pub logical: usize,
+}
+
+/// A syntax error that kept a file from being analyzed
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ParseFailure {
+    pub message: String,
+    /// The 1-based line and column of the error in the file
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The syntax a file is parsed with
+#[derive(Debug, Default, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    TypeScript,
+    Tsx,
+    JavaScript,
+    Jsx,
+}
+
+impl Language {
+    pub fn is_jsx(self) -> bool {
+        matches!(self, Language::Tsx | Language::Jsx)
+    }
+
+    /// The same language with JSX enabled or not
+    pub fn with_jsx(self, jsx: bool) -> Language {
+        match (self, jsx) {
+            (Language::TypeScript | Language::Tsx, false) => Language::TypeScript,
+            (Language::TypeScript | Language::Tsx, true) => Language::Tsx,
+            (Language::JavaScript | Language::Jsx, false) => Language::JavaScript,
+            (Language::JavaScript | Language::Jsx, true) => Language::Jsx,
+        }
+    }
+}
+
+/// Whether a file is parsed as an ES module, or as a script such as a CommonJS module
+#[derive(Debug, Default, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceType {
+    #[default]
+    Module,
+    Script,
+}
+
+/// What a suppression comment suppresses
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SuppressionKind {
+    /// The whole file, for all gates or the listed rules
+    File,
+    /// The next line
+    Line,
+    /// The next function
+    Function,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Suppression {
+    pub kind: SuppressionKind,
+    /// The gates a file suppression applies to, all of them when empty
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<String>,
+    pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Suppression {
+    pub fn applies_to(&self, rule: &str) -> bool {
+        self.rules.is_empty() || self.rules.iter().any(|suppressed| suppressed == rule)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryFileScore {
+    pub file_name: String,
+    pub line_count: usize,
+    pub fta_score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryPoint {
+    pub commit: String,
+    pub date: String,
+    pub file_count: usize,
+    pub line_count: usize,
+    pub average_fta_score: f64,
+    pub max_fta_score: f64,
+    /// Files that couldn't be parsed, which are left out of the other figures
+    #[serde(skip_serializing_if = "is_zero")]
+    pub parse_failures: usize,
+    pub files: Vec<HistoryFileScore>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupSummary {
+    pub name: String,
+    pub file_count: usize,
+    pub line_count: usize,
+    pub average_fta_score: f64,
+    pub weighted_fta_score: f64,
+    pub worst_files: Vec<String>,
+    /// Files of the group that couldn't be parsed, which are left out of the other figures
+    #[serde(skip_serializing_if = "is_zero")]
+    pub parse_failures: usize,
+    /// Files of the group that failed to analyze, which are left out of the other figures
+    #[serde(skip_serializing_if = "is_zero")]
+    pub analysis_failures: usize,
+    /// Files of the group that were skipped, which are left out of the other figures
+    #[serde(skip_serializing_if = "is_zero")]
+    pub skipped: usize,
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
 }
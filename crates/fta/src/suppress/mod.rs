@@ -0,0 +1,243 @@
+use crate::structs::{FileData, Suppression, SuppressionKind};
+use std::collections::HashSet;
+use swc_common::sync::Lrc;
+use swc_common::{SourceMap, Span, Spanned};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{VisitMut, VisitMutWith};
+
+mod tests;
+
+/// Gates that `fta-disable` can turn off for a file
+pub const GATE_RULES: [&str; 1] = ["score-cap"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DirectiveKind {
+    /// `fta-ignore-file`: the file is excluded from every gate
+    IgnoreFile,
+    /// `fta-disable <rule>...`: the file is excluded from the given gates
+    Disable(Vec<String>),
+    /// `fta-ignore-next-line`: the statement on the next line doesn't count towards gates
+    IgnoreNextLine,
+    /// `fta-ignore-next-function`: the next function doesn't count towards gates
+    IgnoreNextFunction,
+}
+
+/// A suppression comment, on `line`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Directive {
+    pub kind: DirectiveKind,
+    pub line: usize,
+    /// The line `fta-ignore-next-*` directives apply to, the first one after `line` that isn't blank
+    pub next_line: usize,
+    pub reason: Option<String>,
+}
+
+/// Parses the text of a comment such as `fta-disable score-cap -- state machine`. The reason is
+/// whatever follows `--`, or for directives without arguments, whatever follows the directive.
+pub fn parse_directive(text: &str, line: usize, next_line: usize) -> Option<Directive> {
+    let text = text.trim().trim_start_matches('*').trim();
+    let (directive, reason) = match text.split_once("--") {
+        Some((directive, reason)) => (directive.trim(), Some(reason.trim())),
+        None => (text, None),
+    };
+    let (name, arguments) = directive
+        .split_once(char::is_whitespace)
+        .map(|(name, arguments)| (name, arguments.trim()))
+        .unwrap_or((directive, ""));
+
+    let reason_or_arguments = || {
+        reason
+            .or(Some(arguments))
+            .filter(|reason| !reason.is_empty())
+            .map(|reason| reason.to_string())
+    };
+
+    let (kind, reason) = match name {
+        "fta-ignore-file" => (DirectiveKind::IgnoreFile, reason_or_arguments()),
+        "fta-ignore-next-line" => (DirectiveKind::IgnoreNextLine, reason_or_arguments()),
+        "fta-ignore-next-function" => (DirectiveKind::IgnoreNextFunction, reason_or_arguments()),
+        "fta-disable" => {
+            let rules: Vec<String> = arguments
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|rule| !rule.is_empty())
+                .map(|rule| rule.to_string())
+                .collect();
+            (
+                DirectiveKind::Disable(rules),
+                reason.filter(|reason| !reason.is_empty()).map(String::from),
+            )
+        }
+        _ => return None,
+    };
+
+    Some(Directive {
+        kind,
+        line,
+        next_line,
+        reason,
+    })
+}
+
+/// Describes the directives for reporting
+pub fn describe(directives: &[Directive]) -> Vec<Suppression> {
+    directives
+        .iter()
+        .map(|directive| {
+            let (kind, rules) = match &directive.kind {
+                DirectiveKind::IgnoreFile => (SuppressionKind::File, vec![]),
+                DirectiveKind::Disable(rules) => (SuppressionKind::File, rules.clone()),
+                DirectiveKind::IgnoreNextLine => (SuppressionKind::Line, vec![]),
+                DirectiveKind::IgnoreNextFunction => (SuppressionKind::Function, vec![]),
+            };
+            let line = match directive.kind {
+                DirectiveKind::IgnoreNextLine | DirectiveKind::IgnoreNextFunction => {
+                    directive.next_line
+                }
+                _ => directive.line,
+            };
+
+            Suppression {
+                kind,
+                rules,
+                line,
+                reason: directive.reason.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Whether the file is excluded from `rule` by a file-wide suppression
+pub fn is_suppressed(file_data: &FileData, rule: &str) -> bool {
+    file_data.suppressions.iter().any(|suppression| {
+        suppression.kind == SuppressionKind::File && suppression.applies_to(rule)
+    })
+}
+
+/// The score `rule` gates the file on, or `None` when the file is suppressed for it
+pub fn gate_score(file_data: &FileData, rule: &str) -> Option<f64> {
+    if is_suppressed(file_data, rule) {
+        return None;
+    }
+
+    Some(file_data.gate_fta_score.unwrap_or(file_data.fta_score))
+}
+
+/// Removes the statements and function bodies targeted by `fta-ignore-next-line` and
+/// `fta-ignore-next-function` directives. Returns the lines that were removed, blank ones included.
+pub fn strip_suppressed(
+    module: &mut Module,
+    directives: &[Directive],
+    source_map: &Lrc<SourceMap>,
+) -> HashSet<usize> {
+    let target_lines = |kind: DirectiveKind| -> HashSet<usize> {
+        directives
+            .iter()
+            .filter(|directive| directive.kind == kind)
+            .map(|directive| directive.next_line)
+            .collect()
+    };
+
+    let mut stripper = SuppressionStripper {
+        source_map,
+        line_targets: target_lines(DirectiveKind::IgnoreNextLine),
+        function_targets: target_lines(DirectiveKind::IgnoreNextFunction),
+        removed_lines: HashSet::new(),
+    };
+    module.visit_mut_with(&mut stripper);
+
+    stripper.removed_lines
+}
+
+struct SuppressionStripper<'a> {
+    source_map: &'a Lrc<SourceMap>,
+    line_targets: HashSet<usize>,
+    function_targets: HashSet<usize>,
+    removed_lines: HashSet<usize>,
+}
+
+impl SuppressionStripper<'_> {
+    fn lines(&self, span: Span) -> (usize, usize) {
+        (
+            self.source_map.lookup_char_pos(span.lo).line,
+            self.source_map.lookup_char_pos(span.hi).line,
+        )
+    }
+
+    fn remove(&mut self, first: usize, last: usize) {
+        self.removed_lines.extend(first..=last);
+    }
+
+    fn is_line_target(&self, span: Span) -> bool {
+        self.line_targets.contains(&self.lines(span).0)
+    }
+
+    /// Empties a function body, keeping the lines of its signature and closing brace
+    fn strip_body(&mut self, function_span: Span, body: &mut BlockStmt) {
+        if !self.function_targets.contains(&self.lines(function_span).0) {
+            return;
+        }
+
+        let (first, last) = self.lines(body.span);
+        if last > first + 1 {
+            self.remove(first + 1, last - 1);
+        }
+        body.stmts.clear();
+    }
+}
+
+impl VisitMut for SuppressionStripper<'_> {
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        items.retain(|item| {
+            let span = match item {
+                ModuleItem::ModuleDecl(decl) => decl.span(),
+                ModuleItem::Stmt(stmt) => stmt.span(),
+            };
+            if self.is_line_target(span) {
+                let (first, last) = self.lines(span);
+                self.remove(first, last);
+                return false;
+            }
+            true
+        });
+        items.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        stmts.retain(|stmt| {
+            let span = stmt.span();
+            if self.is_line_target(span) {
+                let (first, last) = self.lines(span);
+                self.remove(first, last);
+                return false;
+            }
+            true
+        });
+        stmts.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_function(&mut self, function: &mut Function) {
+        if let Some(body) = &mut function.body {
+            self.strip_body(function.span, body);
+        }
+        function.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_arrow_expr(&mut self, arrow: &mut ArrowExpr) {
+        if let BlockStmtOrExpr::Expr(expr) = &*arrow.body {
+            // Treat `() => expr` like `() => { return expr; }`
+            if self.function_targets.contains(&self.lines(arrow.span).0) {
+                let (first, last) = self.lines(expr.span());
+                if last > first {
+                    self.remove(first + 1, last);
+                }
+                *arrow.body = BlockStmtOrExpr::BlockStmt(BlockStmt {
+                    span: expr.span(),
+                    stmts: vec![],
+                });
+            }
+        } else if let BlockStmtOrExpr::BlockStmt(body) = &mut *arrow.body {
+            self.strip_body(arrow.span, body);
+        }
+        arrow.visit_mut_children_with(self);
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use crate::parse::{dialect_for, parse_source};
+    use crate::structs::{ParserOptions, SuppressionKind};
+    use crate::suppress::{describe, parse_directive, strip_suppressed, Directive, DirectiveKind};
+
+    #[test]
+    fn test_parse_directive() {
+        assert_eq!(
+            parse_directive(" fta-ignore-file", 1, 2),
+            Some(Directive {
+                kind: DirectiveKind::IgnoreFile,
+                line: 1,
+                next_line: 2,
+                reason: None,
+            })
+        );
+        assert_eq!(
+            parse_directive(" fta-disable score-cap -- generated parser ", 3, 4),
+            Some(Directive {
+                kind: DirectiveKind::Disable(vec!["score-cap".to_string()]),
+                line: 3,
+                next_line: 4,
+                reason: Some("generated parser".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_directive("* fta-ignore-next-function lookup table ", 7, 9),
+            Some(Directive {
+                kind: DirectiveKind::IgnoreNextFunction,
+                line: 7,
+                next_line: 9,
+                reason: Some("lookup table".to_string()),
+            })
+        );
+        assert_eq!(parse_directive(" fta-unknown", 1, 2), None);
+        assert_eq!(parse_directive(" eslint-disable", 1, 2), None);
+    }
+
+    #[test]
+    fn test_directives_keep_their_lines() {
+        let parsed = parse_source(
+            "a.ts",
+            r#"
+            // fta-ignore-file -- vendored
+
+            const a = 1;
+            // fta-ignore-next-line
+
+            const b = 2; /* fta-disable score-cap */
+            "#,
+            dialect_for("a.ts", &ParserOptions::default()),
+            false,
+            &ParserOptions::default(),
+        );
+        let suppressions = describe(&parsed.directives);
+
+        let described: Vec<(SuppressionKind, usize, Option<&str>)> = suppressions
+            .iter()
+            .map(|s| (s.kind, s.line, s.reason.as_deref()))
+            .collect();
+        assert_eq!(
+            described,
+            vec![
+                (SuppressionKind::File, 2, Some("vendored")),
+                (SuppressionKind::Line, 7, None),
+                (SuppressionKind::File, 7, None),
+            ]
+        );
+        assert_eq!(suppressions[2].rules, vec!["score-cap"]);
+    }
+
+    #[test]
+    fn test_strip_suppressed() {
+        let parsed = parse_source(
+            "a.ts",
+            r#"
+            const kept = 1;
+            // fta-ignore-next-line
+            const removed = {
+                a: 1,
+            };
+            // fta-ignore-next-function
+            function table(key) {
+                switch (key) {
+
+                    case 1: return "a";
+                    default: return "b";
+                }
+            }
+            "#,
+            dialect_for("a.ts", &ParserOptions::default()),
+            false,
+            &ParserOptions::default(),
+        );
+        let mut module = parsed.module.unwrap();
+
+        let mut removed: Vec<usize> =
+            strip_suppressed(&mut module, &parsed.directives, &parsed.source_map)
+                .into_iter()
+                .collect();
+        removed.sort_unstable();
+
+        // Lines are those of the source, starting with a blank one
+        assert_eq!(removed, vec![4, 5, 6, 9, 10, 11, 12, 13]);
+        assert_eq!(module.body.len(), 2);
+    }
+}
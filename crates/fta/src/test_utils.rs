@@ -0,0 +1,34 @@
+use crate::structs::FileData;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Runs git in `dir` with a fixed author, failing the test if it fails
+pub fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=FTA", "-c", "user.email=fta@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// An analyzed file with the given size and score and no other metrics
+pub fn file_data(file_name: &str, line_count: usize, fta_score: f64) -> FileData {
+    FileData {
+        file_name: file_name.to_string(),
+        line_count,
+        fta_score,
+        assessment: "OK".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Writes a file under `root`, creating its parent directories
+pub fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
@@ -0,0 +1,205 @@
+use crate::config::{
+    is_fta_package_json, load_config_content, merge_nested_config, merge_root_config,
+    CONFIG_FILE_NAMES,
+};
+use crate::git::{BlobReader, GitError, TreeEntry};
+use crate::structs::FtaConfigResolved;
+use crate::FTA_IGNORE_FILENAME;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use log::{debug, warn};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+mod tests;
+
+/// The directory of a path in a tree, `""` for the root
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/')
+        .map(|(dir, _)| dir)
+        .unwrap_or_default()
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or_default()
+}
+
+fn read_text(
+    blobs: &mut BlobReader,
+    entry: &TreeEntry,
+    config: &FtaConfigResolved,
+) -> Result<Option<String>, GitError> {
+    let (size, content) = blobs.read(&entry.object_id, config.max_file_size as u64)?;
+    match content.map(String::from_utf8) {
+        Some(Ok(content)) => Ok(Some(content)),
+        Some(Err(_)) => {
+            warn!("Ignoring {}: not valid UTF-8", entry.path);
+            Ok(None)
+        }
+        None => {
+            warn!("Ignoring {}: {} bytes is too large", entry.path, size);
+            Ok(None)
+        }
+    }
+}
+
+/// The config files and .ftaignore files of a git tree, read from its blobs so that analyzing a
+/// revision excludes and configures files the same way a walk of its checkout would.
+///
+/// The config file of the root replaces the one the base config was resolved from, keeping its
+/// command-line settings, so that a revision is analyzed with its own config even in a bare
+/// repository. Like in a walk, the config file of a subdirectory applies to its subtree on top of
+/// the config of its parent directories. Presets they `extends` are resolved from `preset_root`,
+/// the project on disk.
+pub struct Tree<'a> {
+    rev: &'a str,
+    preset_root: &'a Path,
+    base_config: &'a FtaConfigResolved,
+    config_files: HashMap<&'a str, Vec<&'a TreeEntry>>,
+    ignores: HashMap<&'a str, Gitignore>,
+    configs: HashMap<String, Option<Arc<FtaConfigResolved>>>,
+}
+
+impl<'a> Tree<'a> {
+    /// Collects the config files of the tree listed in `entries` and reads its .ftaignore files
+    pub fn new(
+        entries: &'a [TreeEntry],
+        rev: &'a str,
+        preset_root: &'a Path,
+        base_config: &'a FtaConfigResolved,
+        blobs: &mut BlobReader,
+    ) -> Result<Tree<'a>, GitError> {
+        let mut config_files: HashMap<&str, Vec<&TreeEntry>> = HashMap::new();
+        let mut ignores: HashMap<&str, Gitignore> = HashMap::new();
+
+        for entry in entries {
+            let dir = parent_dir(&entry.path);
+            let file_name = file_name(&entry.path);
+
+            if CONFIG_FILE_NAMES.contains(&file_name) {
+                config_files.entry(dir).or_default().push(entry);
+            } else if file_name == FTA_IGNORE_FILENAME {
+                let Some(content) = read_text(blobs, entry, base_config)? else {
+                    continue;
+                };
+                let mut builder = GitignoreBuilder::new("");
+                for line in content.lines() {
+                    if let Err(err) = builder.add_line(None, line) {
+                        warn!("Ignoring invalid pattern in {}: {}", entry.path, err);
+                    }
+                }
+                if let Ok(gitignore) = builder.build() {
+                    ignores.insert(dir, gitignore);
+                }
+            }
+        }
+
+        // Config files are looked up in the same order as on disk
+        let precedence = |entry: &&TreeEntry| {
+            CONFIG_FILE_NAMES
+                .iter()
+                .position(|name| *name == file_name(&entry.path))
+        };
+        for candidates in config_files.values_mut() {
+            candidates.sort_by_key(precedence);
+        }
+
+        Ok(Tree {
+            rev,
+            preset_root,
+            base_config,
+            config_files,
+            ignores,
+            configs: HashMap::new(),
+        })
+    }
+
+    /// Whether a .ftaignore file of the tree excludes the file at `path`. The .ftaignore closest to
+    /// the file that matches it decides, like the nested ignore files of a walk.
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let mut dir = parent_dir(path);
+        loop {
+            if let Some(gitignore) = self.ignores.get(dir) {
+                let relative = path
+                    .strip_prefix(dir)
+                    .unwrap_or(path)
+                    .trim_start_matches('/');
+                let matched = gitignore.matched_path_or_any_parents(relative, false);
+                if matched.is_ignore() {
+                    return true;
+                }
+                if matched.is_whitelist() {
+                    return false;
+                }
+            }
+            if dir.is_empty() {
+                return false;
+            }
+            dir = parent_dir(dir);
+        }
+    }
+
+    /// Reads the config file of `dir`, if it has one
+    fn read_config_file(
+        &self,
+        dir: &str,
+        blobs: &mut BlobReader,
+    ) -> Result<Option<(String, String)>, GitError> {
+        let candidates = self.config_files.get(dir).cloned().unwrap_or_default();
+
+        for entry in candidates {
+            let Some(content) = read_text(blobs, entry, self.base_config)? else {
+                continue;
+            };
+            if file_name(&entry.path) == "package.json" && !is_fta_package_json(&content) {
+                continue;
+            }
+            return Ok(Some((entry.path.clone(), content)));
+        }
+
+        Ok(None)
+    }
+
+    /// The config of the files in `dir`, see `Workspace::config_for_dir`. `None` means the base
+    /// config applies. Fails when one of the config files involved is invalid.
+    fn config_for_dir(
+        &mut self,
+        dir: &str,
+        blobs: &mut BlobReader,
+    ) -> Result<Option<Arc<FtaConfigResolved>>, GitError> {
+        if let Some(config) = self.configs.get(dir) {
+            return Ok(config.clone());
+        }
+
+        let parent_config = match dir {
+            "" => None,
+            _ => self.config_for_dir(parent_dir(dir), blobs)?,
+        };
+        let config = match self.read_config_file(dir, blobs)? {
+            Some((config_path, content)) => {
+                let source = format!("{}:{}", self.rev, config_path);
+                let provided_config =
+                    load_config_content(&source, &content, &self.preset_root.join(dir))?;
+                let base = parent_config.as_deref().unwrap_or(self.base_config);
+                debug!("Applying {} to {}", source, dir);
+                Some(Arc::new(match dir {
+                    "" => merge_root_config(base, provided_config),
+                    _ => merge_nested_config(base, provided_config, dir, dir),
+                }))
+            }
+            None => parent_config,
+        };
+
+        self.configs.insert(dir.to_string(), config.clone());
+        Ok(config)
+    }
+
+    /// The config of the file at `path`, taking the config files of the tree into account
+    pub fn config_for(
+        &mut self,
+        path: &str,
+        blobs: &mut BlobReader,
+    ) -> Result<Option<Arc<FtaConfigResolved>>, GitError> {
+        self.config_for_dir(parent_dir(path), blobs)
+    }
+}
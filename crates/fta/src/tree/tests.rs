@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::get_default_config;
+    use crate::git::GitRepository;
+    use crate::test_utils::{git, write};
+    use crate::tree::Tree;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn commit_files(files: &[(&str, &str)]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "--quiet"]);
+        for (path, content) in files {
+            write(dir.path(), path, content);
+        }
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "--quiet", "-m", "files"]);
+        dir
+    }
+
+    #[test]
+    fn test_config_files_apply_to_their_subtree() {
+        let dir = commit_files(&[
+            (
+                "packages/foo/fta.json",
+                r#"{ "score_cap": 1, "exclude_directories": ["/gen"], "overrides": [{ "files": ["*.spec.ts"], "score_cap": 2 }] }"#,
+            ),
+            ("packages/foo/.ftarc.json", r#"{ "score_cap": 3 }"#),
+            ("packages/foo/sub/fta.yaml", "exclude_under: 1000\n"),
+            ("packages/bar/package.json", r#"{ "name": "bar" }"#),
+            (
+                "packages/baz/package.json",
+                r#"{ "fta": { "score_cap": 4 } }"#,
+            ),
+            ("fta.json", r#"{ "score_cap": 5 }"#),
+        ]);
+        let repository = GitRepository::open(dir.path().to_str().unwrap()).unwrap();
+        let entries = repository.list_files("HEAD").unwrap();
+        let mut blobs = repository.blob_reader().unwrap();
+        let mut config = get_default_config();
+        config.score_cap = 90;
+        let mut tree = Tree::new(&entries, "HEAD", Path::new(""), &config, &mut blobs).unwrap();
+
+        let foo_config = tree
+            .config_for("packages/foo/x.ts", &mut blobs)
+            .unwrap()
+            .unwrap();
+        assert_eq!(foo_config.score_cap, 1);
+        assert!(foo_config
+            .exclude_directories
+            .contains(&"/packages/foo/gen".to_string()));
+        assert_eq!(
+            foo_config.overrides[0].files,
+            Some(vec!["packages/foo/**/*.spec.ts".to_string()])
+        );
+
+        let sub_config = tree
+            .config_for("packages/foo/sub/y.ts", &mut blobs)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sub_config.score_cap, 1);
+        assert_eq!(sub_config.exclude_under, 1000);
+
+        let baz_config = tree
+            .config_for("packages/baz/z.ts", &mut blobs)
+            .unwrap()
+            .unwrap();
+        assert_eq!(baz_config.score_cap, 4);
+
+        // The root's config file applies, and package.json only counts with an "fta" key
+        let bar_config = tree
+            .config_for("packages/bar/z.ts", &mut blobs)
+            .unwrap()
+            .unwrap();
+        assert_eq!(bar_config.score_cap, 5);
+        let root_config = tree.config_for("x.ts", &mut blobs).unwrap().unwrap();
+        assert_eq!(root_config.score_cap, 5);
+    }
+
+    #[test]
+    fn test_root_config_file_replaces_the_base_config() {
+        let dir = commit_files(&[("fta.json", r#"{ "score_cap": 5, "exclude_under": 1 }"#)]);
+        let repository = GitRepository::open(dir.path().to_str().unwrap()).unwrap();
+        let entries = repository.list_files("HEAD").unwrap();
+        let mut blobs = repository.blob_reader().unwrap();
+        let mut config = get_default_config();
+        config.output_limit = 3;
+        config.exclude_under = 10;
+        config.command_line.exclude_under = Some(10);
+        let mut tree = Tree::new(&entries, "HEAD", Path::new(""), &config, &mut blobs).unwrap();
+
+        // Only the command-line settings of the base config are kept
+        let root_config = tree.config_for("x.ts", &mut blobs).unwrap().unwrap();
+        assert_eq!(root_config.score_cap, 5);
+        assert_eq!(root_config.exclude_under, 10);
+        assert_eq!(root_config.output_limit, get_default_config().output_limit);
+    }
+
+    #[test]
+    fn test_invalid_config_files_are_errors() {
+        let dir = commit_files(&[("pkg/fta.json", r#"{ "score_cap": "high" }"#)]);
+        let repository = GitRepository::open(dir.path().to_str().unwrap()).unwrap();
+        let entries = repository.list_files("HEAD").unwrap();
+        let mut blobs = repository.blob_reader().unwrap();
+        let config = get_default_config();
+        let mut tree = Tree::new(&entries, "HEAD", Path::new(""), &config, &mut blobs).unwrap();
+
+        let error = tree.config_for("pkg/x.ts", &mut blobs).unwrap_err();
+        assert!(error.to_string().contains("HEAD:pkg/fta.json"));
+    }
+
+    #[test]
+    fn test_ftaignore_files_exclude_paths() {
+        let dir = commit_files(&[
+            (".ftaignore", "/gen/\nlegacy.ts\n"),
+            ("src/api/.ftaignore", "generated/\n!legacy.ts\n"),
+        ]);
+        let repository = GitRepository::open(dir.path().to_str().unwrap()).unwrap();
+        let entries = repository.list_files("HEAD").unwrap();
+        let mut blobs = repository.blob_reader().unwrap();
+        let config = get_default_config();
+        let tree = Tree::new(&entries, "HEAD", Path::new(""), &config, &mut blobs).unwrap();
+
+        assert!(tree.is_ignored("gen/x.ts"));
+        assert!(tree.is_ignored("src/legacy.ts"));
+        assert!(tree.is_ignored("src/api/generated/client.ts"));
+        assert!(!tree.is_ignored("src/api/legacy.ts"));
+        assert!(!tree.is_ignored("src/gen/x.ts"));
+        assert!(!tree.is_ignored("src/x.ts"));
+    }
+}
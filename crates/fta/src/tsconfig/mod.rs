@@ -0,0 +1,228 @@
+use crate::structs::ParserOptions;
+use log::{debug, warn};
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+mod tests;
+
+/// The TypeScript config files looked up in each directory, in order of precedence
+const TSCONFIG_FILE_NAMES: [&str; 2] = ["tsconfig.json", "jsconfig.json"];
+
+/// Reads the parser options of files from their nearest tsconfig.json or jsconfig.json, looking in
+/// the file's directory and then its parents. Each directory is only looked up once.
+#[derive(Default)]
+pub struct TsConfigs {
+    options: RefCell<HashMap<PathBuf, Option<Rc<ParserOptions>>>>,
+}
+
+impl TsConfigs {
+    pub fn new() -> TsConfigs {
+        TsConfigs::default()
+    }
+
+    /// The parser options of the file at `path`, if a tsconfig.json or jsconfig.json applies to it
+    pub fn parser_options_for(&self, path: &Path) -> Option<Rc<ParserOptions>> {
+        // The file may not exist, e.g. when naming source code piped through stdin
+        let dir = path
+            .ancestors()
+            .skip(1)
+            .chain([Path::new(".")])
+            .find_map(|dir| fs::canonicalize(dir).ok())?;
+
+        self.options_for_dir(&dir)
+    }
+
+    fn options_for_dir(&self, dir: &Path) -> Option<Rc<ParserOptions>> {
+        if let Some(options) = self.options.borrow().get(dir) {
+            return options.clone();
+        }
+
+        let tsconfig_path = TSCONFIG_FILE_NAMES
+            .iter()
+            .map(|file_name| dir.join(file_name))
+            .find(|path| path.is_file());
+        let options = match tsconfig_path {
+            Some(tsconfig_path) => match read_tsconfig(&tsconfig_path) {
+                Ok(options) => {
+                    debug!("Using parser options from {}", tsconfig_path.display());
+                    Some(Rc::new(options))
+                }
+                Err(err) => {
+                    warn!("Ignoring {}: {}", tsconfig_path.display(), err);
+                    None
+                }
+            },
+            None => dir.parent().and_then(|parent| self.options_for_dir(parent)),
+        };
+
+        self.options
+            .borrow_mut()
+            .insert(dir.to_path_buf(), options.clone());
+        options
+    }
+}
+
+/// Reads the parser options set by a tsconfig.json or jsconfig.json and the configs it `extends`
+pub fn read_tsconfig(path: &Path) -> Result<ParserOptions, String> {
+    let compiler_options = read_compiler_options(path, &mut Vec::new())?;
+
+    Ok(ParserOptions {
+        decorators: compiler_options
+            .get("experimentalDecorators")
+            .and_then(Value::as_bool),
+        jsx: compiler_options
+            .get("jsx")
+            .and_then(Value::as_str)
+            .map(|_| true),
+        target: compiler_options
+            .get("target")
+            .and_then(Value::as_str)
+            .map(|target| target.to_ascii_lowercase()),
+    })
+}
+
+/// The `compilerOptions` of a tsconfig.json, on top of those of the configs it `extends`
+fn read_compiler_options(
+    path: &Path,
+    chain: &mut Vec<PathBuf>,
+) -> Result<Map<String, Value>, String> {
+    let canonical_path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if chain.contains(&canonical_path) {
+        return Err(format!("circular extends through {}", path.display()));
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("unable to read {}: {}", path.display(), err))?;
+    let tsconfig: Value = serde_json::from_str(&strip_jsonc(&content))
+        .map_err(|err| format!("invalid JSON in {}: {}", path.display(), err))?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    // Since TypeScript 5.0, `extends` can list several configs, later ones taking precedence
+    let extends: Vec<&str> = match tsconfig.get("extends") {
+        Some(Value::String(extends)) => vec![extends.as_str()],
+        Some(Value::Array(extends)) => extends.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+
+    chain.push(canonical_path);
+    let mut compiler_options = Map::new();
+    for extended in extends {
+        let extended_path = resolve_extends(extended, dir).ok_or_else(|| {
+            format!(
+                "unable to find the config \"{}\" extended by {}",
+                extended,
+                path.display()
+            )
+        })?;
+        compiler_options.extend(read_compiler_options(&extended_path, chain)?);
+    }
+    chain.pop();
+
+    if let Some(Value::Object(own_options)) = tsconfig.get("compilerOptions") {
+        compiler_options.extend(own_options.clone());
+    }
+
+    Ok(compiler_options)
+}
+
+/// Finds the config of a tsconfig.json `extends` entry: a path relative to the extending config,
+/// or a package (or a file inside one) under `node_modules` in its directory or its parents.
+fn resolve_extends(extended: &str, dir: &Path) -> Option<PathBuf> {
+    let resolve = |path: PathBuf| -> Option<PathBuf> {
+        if path.is_file() {
+            return Some(path);
+        }
+        let with_extension = PathBuf::from(format!("{}.json", path.display()));
+        if with_extension.is_file() {
+            return Some(with_extension);
+        }
+        let in_package = path.join("tsconfig.json");
+        in_package.is_file().then_some(in_package)
+    };
+
+    if extended.starts_with("./")
+        || extended.starts_with("../")
+        || Path::new(extended).is_absolute()
+    {
+        return resolve(dir.join(extended));
+    }
+
+    dir.ancestors()
+        .map(|ancestor| ancestor.join("node_modules").join(extended))
+        .find_map(resolve)
+}
+
+/// Turns the JSON with comments and trailing commas that TypeScript accepts into plain JSON
+fn strip_jsonc(content: &str) -> String {
+    let mut json = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            match c {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('/', Some('/')) => while chars.next_if(|&next| next != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+                json.push(' ');
+            }
+            _ => {
+                in_string = c == '"';
+                json.push(c);
+            }
+        }
+    }
+
+    strip_trailing_commas(&json)
+}
+
+/// Removes the commas directly followed by a closing bracket, outside of strings
+fn strip_trailing_commas(json: &str) -> String {
+    let mut stripped = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut chars = json.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            match c {
+                '\\' => stripped.extend(chars.next().map(|(_, escaped)| escaped)),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        let is_trailing_comma = c == ','
+            && matches!(
+                json[index + 1..].trim_start().chars().next(),
+                Some('}') | Some(']')
+            );
+        if !is_trailing_comma {
+            in_string = c == '"';
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+    use crate::structs::ParserOptions;
+    use crate::test_utils::write;
+    use crate::tsconfig::{read_tsconfig, strip_jsonc, TsConfigs};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let json = strip_jsonc(
+            r#"{
+                // Comment
+                "compilerOptions": {
+                    "paths": { "@/*": ["src/*"], }, /* block
+                    comment */
+                    "outDir": "dist//out", "note": "a \" /* b */ ,}",
+                },
+            }"#,
+        );
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["compilerOptions"]["outDir"], "dist//out");
+        assert_eq!(value["compilerOptions"]["note"], "a \" /* b */ ,}");
+    }
+
+    #[test]
+    fn test_read_tsconfig_follows_extends() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "node_modules/@tsconfig/node18/tsconfig.json",
+            r#"{ "compilerOptions": { "target": "ES2022", "experimentalDecorators": false } }"#,
+        );
+        write(
+            root,
+            "tsconfig.base.json",
+            r#"{ "compilerOptions": { "experimentalDecorators": true, "jsx": "react-jsx" } }"#,
+        );
+        write(
+            root,
+            "apps/api/tsconfig.json",
+            r#"{
+                // Later configs take precedence
+                "extends": ["@tsconfig/node18", "../../tsconfig.base"],
+                "compilerOptions": { "target": "ESNext", },
+            }"#,
+        );
+
+        let options = read_tsconfig(&root.join("apps/api/tsconfig.json")).unwrap();
+        assert_eq!(
+            options,
+            ParserOptions {
+                decorators: Some(true),
+                jsx: Some(true),
+                target: Some("esnext".to_string()),
+            }
+        );
+
+        write(root, "a.json", r#"{ "extends": "./b.json" }"#);
+        write(root, "b.json", r#"{ "extends": "./a.json" }"#);
+        let error = read_tsconfig(&root.join("a.json")).unwrap_err();
+        assert!(error.starts_with("circular extends"), "{}", error);
+    }
+
+    #[test]
+    fn test_nearest_tsconfig_applies() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "tsconfig.json",
+            r#"{ "compilerOptions": { "target": "es2019" } }"#,
+        );
+        write(
+            root,
+            "web/jsconfig.json",
+            r#"{ "compilerOptions": { "jsx": "preserve" } }"#,
+        );
+        write(root, "broken/tsconfig.json", "{");
+
+        let tsconfigs = TsConfigs::new();
+        let options_for = |file_name: &str| {
+            tsconfigs
+                .parser_options_for(&root.join(file_name))
+                .map(|options| (*options).clone())
+        };
+
+        assert_eq!(
+            options_for("src/deep/a.ts").and_then(|options| options.target),
+            Some("es2019".to_string())
+        );
+        assert_eq!(
+            options_for("web/app.js"),
+            Some(ParserOptions {
+                jsx: Some(true),
+                ..Default::default()
+            })
+        );
+        assert_eq!(options_for("broken/a.ts"), None);
+    }
+}
@@ -1,42 +1,119 @@
 use crate::structs::FtaConfigResolved;
-use globset::{Glob, GlobSetBuilder};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use ignore::DirEntry;
 use log::warn;
+use std::path::Path;
 
 mod tests;
 
-pub fn is_excluded_filename(file_name: &str, patterns: &[String]) -> bool {
+/// Compiles the `exclude_filenames` patterns, warning about the invalid ones
+pub fn exclude_filenames_matcher(patterns: &[String]) -> GlobSet {
     let mut builder = GlobSetBuilder::new();
 
     for pattern in patterns {
-        let glob = Glob::new(pattern).unwrap();
-        builder.add(glob);
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(err) => warn!(
+                "Ignoring invalid exclude_filenames pattern {}: {}",
+                pattern, err
+            ),
+        }
     }
 
-    let glob_set = builder.build().unwrap();
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+pub fn is_excluded_filename(file_name: &str, matcher: &GlobSet) -> bool {
+    matcher.is_match(file_name)
+}
+
+/// Compiles the `exclude_directories` patterns, warning about the invalid ones. Patterns use the
+/// gitignore syntax relative to the analyzed root, so `/dist` only matches the root's dist
+/// directory, `dist` any of them, and `!src/bin` re-includes a directory excluded by an earlier
+/// pattern.
+pub fn exclude_directories_matcher(patterns: &[String]) -> Gitignore {
+    let mut builder = GitignoreBuilder::new("");
+    for pattern in patterns {
+        if let Err(err) = builder.add_line(None, pattern) {
+            warn!(
+                "Ignoring invalid exclude_directories pattern {}: {}",
+                pattern, err
+            );
+        }
+    }
 
-    glob_set.is_match(file_name)
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
 }
 
-pub fn is_valid_file(repo_path: &String, entry: &DirEntry, config: &FtaConfigResolved) -> bool {
-    let file_name = entry.path().file_name().unwrap().to_str().unwrap();
-    let relative_path = entry
-        .path()
+/// Whether the file at `relative_path` is inside a directory excluded by `matcher`, see
+/// `exclude_directories_matcher`
+pub fn is_excluded_directory(relative_path: &str, matcher: &Gitignore) -> bool {
+    match Path::new(relative_path).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => {
+            matcher.matched_path_or_any_parents(dir, true).is_ignore()
+        }
+        _ => false,
+    }
+}
+
+pub fn is_valid_file(repo_path: &str, entry: &DirEntry, config: &FtaConfigResolved) -> bool {
+    let path = entry.path();
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let relative_path = path
         .strip_prefix(repo_path)
-        .unwrap()
-        .to_str()
-        .unwrap();
+        .unwrap_or(path)
+        .to_string_lossy();
+
+    is_valid_path(&file_name, &relative_path, config)
+}
 
+/// Applies the extension and exclusion rules to a file that may not exist on disk,
+/// e.g. one read from a git tree.
+pub fn is_valid_path(file_name: &str, relative_path: &str, config: &FtaConfigResolved) -> bool {
     let valid_extension = config.extensions.iter().any(|ext| file_name.ends_with(ext));
-    let is_excluded_filename = is_excluded_filename(file_name, &config.exclude_filenames);
-    let is_excluded_directory = config
-        .exclude_directories
-        .iter()
-        .any(|dir| relative_path.starts_with(dir));
+    let matchers = config.matchers();
+    let is_excluded_filename = is_excluded_filename(file_name, &matchers.exclude_filenames);
+    let is_excluded_directory = is_excluded_directory(relative_path, &matchers.exclude_directories);
 
     valid_extension && !is_excluded_filename && !is_excluded_directory
 }
 
+/// The name a file is reported under: its path relative to the analyzed root, or the path as given
+/// when the root is the file itself.
+pub fn get_file_name(path: &Path, repo_path: &str) -> String {
+    match path.strip_prefix(repo_path) {
+        Ok(relative) if !relative.as_os_str().is_empty() => relative.display().to_string(),
+        _ => path.display().to_string(),
+    }
+}
+
+/// Splits a glob pattern into the directory to walk (the components before the first one
+/// containing a glob character) and the full pattern, normalized without a leading `./`.
+/// The pattern is empty if `path` isn't a glob at all.
+pub fn split_glob(path: &str) -> (String, String) {
+    let pattern = path.trim_start_matches("./");
+    let is_glob = |component: &str| component.contains(['*', '?', '[', '{']);
+
+    if !is_glob(pattern) {
+        return (path.to_string(), String::new());
+    }
+
+    let base: Vec<&str> = pattern.split('/').take_while(|c| !is_glob(c)).collect();
+    let base = if base.is_empty() {
+        ".".to_string()
+    } else {
+        base.join("/")
+    };
+
+    (base, pattern.to_string())
+}
+
 pub fn warn_about_language(file_name: &str, use_tsx: bool) {
     let tsx_name = if use_tsx { "j/tsx" } else { "non-j/tsx" };
     let opposite_tsx_name = if use_tsx { "non-j/tsx" } else { "j/tsx" };
@@ -1,6 +1,10 @@
 #[cfg(test)]
 mod tests {
-    use crate::utils::{get_assessment, is_excluded_filename};
+    use crate::utils::{
+        exclude_directories_matcher, exclude_filenames_matcher, get_assessment, get_file_name,
+        is_excluded_directory, is_excluded_filename, split_glob,
+    };
+    use std::path::Path;
 
     #[test]
     fn test_get_assessment_ok() {
@@ -22,19 +26,84 @@ mod tests {
 
     #[test]
     fn test_is_excluded_filename_a() {
-        let pattern = String::from("*/naughty/*.ts");
-        let mut patterns = Vec::new();
-        patterns.push(pattern);
-        let result = is_excluded_filename("path/to/naughty/file.ts", &patterns);
-        assert_eq!(result, true);
+        let patterns = vec![String::from("*/naughty/*.ts")];
+        let result = is_excluded_filename(
+            "path/to/naughty/file.ts",
+            &exclude_filenames_matcher(&patterns),
+        );
+        assert!(result);
     }
 
     #[test]
     fn test_is_excluded_filename_b() {
-        let pattern = String::from("*/naughty/*.ts");
-        let mut patterns = Vec::new();
-        patterns.push(pattern);
-        let result = is_excluded_filename("path/to/sensible/file.ts", &patterns);
-        assert_eq!(result, false);
+        let patterns = vec![String::from("*/naughty/*.ts")];
+        let result = is_excluded_filename(
+            "path/to/sensible/file.ts",
+            &exclude_filenames_matcher(&patterns),
+        );
+        assert!(!result);
+    }
+
+    #[test]
+    fn test_get_file_name_relative_to_root() {
+        let file_name = get_file_name(Path::new("project/src/foo.ts"), "project");
+        assert_eq!(file_name, "src/foo.ts");
+    }
+
+    #[test]
+    fn test_get_file_name_when_root_is_the_file() {
+        let file_name = get_file_name(Path::new("project/src/foo.ts"), "project/src/foo.ts");
+        assert_eq!(file_name, "project/src/foo.ts");
+    }
+
+    #[test]
+    fn test_split_glob() {
+        assert_eq!(
+            split_glob("src/**/*.ts"),
+            ("src".to_string(), "src/**/*.ts".to_string())
+        );
+        assert_eq!(
+            split_glob("./packages/*/src/*.tsx"),
+            ("packages".to_string(), "packages/*/src/*.tsx".to_string())
+        );
+        assert_eq!(split_glob("*.ts"), (".".to_string(), "*.ts".to_string()));
+        assert_eq!(
+            split_glob("src/foo.ts"),
+            ("src/foo.ts".to_string(), String::new())
+        );
+    }
+
+    #[test]
+    fn test_is_excluded_directory() {
+        let patterns: Vec<String> = ["/dist", "build/", "**/fixtures", "/bin", "node_*"]
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect();
+        let matcher = exclude_directories_matcher(&patterns);
+
+        assert!(is_excluded_directory("dist/index.js", &matcher));
+        assert!(!is_excluded_directory("src/dist/index.js", &matcher));
+        assert!(is_excluded_directory("packages/a/build/index.js", &matcher));
+        assert!(is_excluded_directory("src/fixtures/deep/x.ts", &matcher));
+        assert!(is_excluded_directory("bin/cli.ts", &matcher));
+        assert!(!is_excluded_directory("src/bin/cli.ts", &matcher));
+        assert!(is_excluded_directory("node_stuff/x.ts", &matcher));
+        // Only directories are matched, not the file itself
+        assert!(!is_excluded_directory("dist", &matcher));
+        assert!(!is_excluded_directory("src/build.ts", &matcher));
+    }
+
+    #[test]
+    fn test_is_excluded_directory_with_negation() {
+        let patterns: Vec<String> = ["bin", "!src/bin"]
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect();
+        let matcher = exclude_directories_matcher(&patterns);
+
+        assert!(is_excluded_directory("bin/cli.ts", &matcher));
+        assert!(is_excluded_directory("lib/bin/cli.ts", &matcher));
+        assert!(!is_excluded_directory("src/bin/cli.ts", &matcher));
+        assert!(!is_excluded_directory("src/bin/nested/cli.ts", &matcher));
     }
 }
@@ -0,0 +1,262 @@
+use crate::config::{find_config_in, load_config, merge_nested_config, ConfigError};
+use crate::structs::FtaConfigResolved;
+use crate::utils::get_file_name;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use log::{debug, warn};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+mod tests;
+
+#[derive(Debug)]
+pub struct Package {
+    pub name: String,
+}
+
+/// The layout of a (mono)repo: its packages and nested config files.
+///
+/// Package boundaries come from npm/yarn `workspaces`, `pnpm-workspace.yaml` and nested
+/// `package.json` files. When the root declares workspaces, only the matching directories are
+/// treated as packages; otherwise every directory with a `package.json` is.
+///
+/// A config file in a subdirectory, e.g. a package's own fta.json, applies to that subtree on top of
+/// the config of its parent directories.
+pub struct Workspace<'a> {
+    root: PathBuf,
+    name_root: &'a str,
+    base_config: &'a FtaConfigResolved,
+    includes: Option<GlobSet>,
+    excludes: GlobSet,
+    packages: RefCell<HashMap<PathBuf, Option<Rc<Package>>>>,
+    configs: RefCell<HashMap<PathBuf, Option<Arc<FtaConfigResolved>>>>,
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let content = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            warn!("Ignoring invalid {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|patterns| {
+            patterns
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|pattern| pattern.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads the workspace globs declared by `package.json` (`workspaces` as a list or as
+/// `{ "packages": [...] }`) and `pnpm-workspace.yaml`.
+pub fn read_workspace_patterns(root: &Path) -> Option<Vec<String>> {
+    let mut patterns: Option<Vec<String>> = None;
+
+    if let Some(package_json) = read_json(&root.join("package.json")) {
+        let workspaces = package_json.get("workspaces");
+        let declared = match workspaces {
+            Some(Value::Array(_)) => string_list(workspaces),
+            Some(Value::Object(object)) => string_list(object.get("packages")),
+            _ => Vec::new(),
+        };
+        if workspaces.is_some() {
+            patterns.get_or_insert_with(Vec::new).extend(declared);
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        match serde_yaml_ng::from_str::<serde_yaml_ng::Value>(&content) {
+            Ok(pnpm_workspace) => {
+                let declared = pnpm_workspace
+                    .get("packages")
+                    .and_then(serde_yaml_ng::Value::as_sequence)
+                    .map(|packages| {
+                        packages
+                            .iter()
+                            .filter_map(serde_yaml_ng::Value::as_str)
+                            .map(|pattern| pattern.to_string())
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                patterns.get_or_insert_with(Vec::new).extend(declared);
+            }
+            Err(err) => warn!("Ignoring invalid pnpm-workspace.yaml: {}", err),
+        }
+    }
+
+    patterns
+}
+
+fn build_glob_set(patterns: &[&str]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        match GlobBuilder::new(pattern).literal_separator(true).build() {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(err) => warn!("Ignoring invalid workspace pattern {}: {}", pattern, err),
+        }
+    }
+
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+impl<'a> Workspace<'a> {
+    /// `name_root` is the directory file names are reported relative to, which the `files` globs of
+    /// nested configs' overrides are rebased onto.
+    pub fn new(
+        root: &str,
+        name_root: &'a str,
+        base_config: &'a FtaConfigResolved,
+    ) -> Workspace<'a> {
+        let root = PathBuf::from(root);
+        let patterns = read_workspace_patterns(&root);
+
+        let (includes, excludes) = match &patterns {
+            Some(patterns) => {
+                let (negated, positive): (Vec<&str>, Vec<&str>) = patterns
+                    .iter()
+                    .map(|pattern| pattern.as_str())
+                    .partition(|pattern| pattern.starts_with('!'));
+                let negated: Vec<&str> = negated.iter().map(|pattern| &pattern[1..]).collect();
+                (Some(build_glob_set(&positive)), build_glob_set(&negated))
+            }
+            None => (None, GlobSet::empty()),
+        };
+        debug!("Workspace patterns for {}: {:?}", root.display(), patterns);
+
+        Workspace {
+            root,
+            name_root,
+            base_config,
+            includes,
+            excludes,
+            packages: RefCell::new(HashMap::new()),
+            configs: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn is_package_dir(&self, dir: &Path) -> bool {
+        if !dir.join("package.json").is_file() {
+            return false;
+        }
+
+        let relative = match dir.strip_prefix(&self.root) {
+            Ok(relative) if relative.as_os_str().is_empty() => return true,
+            Ok(relative) => relative,
+            Err(_) => return false,
+        };
+
+        match &self.includes {
+            Some(includes) => includes.is_match(relative) && !self.excludes.is_match(relative),
+            None => true,
+        }
+    }
+
+    fn load_package(&self, dir: &Path) -> Option<Rc<Package>> {
+        if !self.is_package_dir(dir) {
+            return None;
+        }
+
+        let relative_dir = dir.strip_prefix(&self.root).unwrap_or(dir);
+        let name = read_json(&dir.join("package.json"))
+            .and_then(|package_json| {
+                package_json
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(|name| name.to_string())
+            })
+            .unwrap_or_else(|| relative_dir.display().to_string());
+
+        Some(Rc::new(Package { name }))
+    }
+
+    /// Finds the innermost package containing `path`
+    pub fn package_for(&self, path: &Path) -> Option<Rc<Package>> {
+        for dir in path.ancestors().skip(1) {
+            if !dir.starts_with(&self.root) {
+                break;
+            }
+
+            let cached = self.packages.borrow().get(dir).cloned();
+            let package = match cached {
+                Some(package) => package,
+                None => {
+                    let package = self.load_package(dir);
+                    self.packages
+                        .borrow_mut()
+                        .insert(dir.to_path_buf(), package.clone());
+                    package
+                }
+            };
+
+            if package.is_some() {
+                return package;
+            }
+        }
+
+        None
+    }
+
+    /// The config of the files in `dir`: the base config with the config files of `dir` and its
+    /// parent directories up to the root layered on top. `None` means the base config applies.
+    /// Fails when one of these config files is invalid.
+    fn config_for_dir(&self, dir: &Path) -> Result<Option<Arc<FtaConfigResolved>>, ConfigError> {
+        // The root's config is the base config already
+        if dir == self.root || !dir.starts_with(&self.root) {
+            return Ok(None);
+        }
+        if let Some(config) = self.configs.borrow().get(dir) {
+            return Ok(config.clone());
+        }
+
+        let parent_config = match dir.parent() {
+            Some(parent) => self.config_for_dir(parent)?,
+            None => None,
+        };
+        let config = match find_config_in(dir) {
+            Some(config_path) => {
+                let provided_config = load_config(&config_path.display().to_string())?;
+                let base = parent_config.as_deref().unwrap_or(self.base_config);
+                let prefix = get_file_name(dir, self.name_root);
+                let relative_dir = dir.strip_prefix(&self.root).unwrap_or(dir);
+                debug!("Applying {} to {}", config_path.display(), prefix);
+                Some(Arc::new(merge_nested_config(
+                    base,
+                    provided_config,
+                    &prefix,
+                    &relative_dir.to_string_lossy(),
+                )))
+            }
+            None => parent_config,
+        };
+
+        self.configs
+            .borrow_mut()
+            .insert(dir.to_path_buf(), config.clone());
+        Ok(config)
+    }
+
+    /// The config of the file at `path`, taking nested config files into account
+    pub fn config_for(&self, path: &Path) -> Result<Option<Arc<FtaConfigResolved>>, ConfigError> {
+        match path.parent() {
+            Some(dir) => self.config_for_dir(dir),
+            None => Ok(None),
+        }
+    }
+}
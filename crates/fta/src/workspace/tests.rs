@@ -0,0 +1,259 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::{
+        apply_overrides, apply_setting, get_default_config, parse_setting, ConfigSources,
+    };
+    use crate::test_utils::write;
+    use crate::utils::is_valid_path;
+    use crate::workspace::{read_workspace_patterns, Workspace};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_workspace_patterns() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(read_workspace_patterns(dir.path()), None);
+
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "workspaces": ["packages/*"] }"#,
+        );
+        assert_eq!(
+            read_workspace_patterns(dir.path()),
+            Some(vec!["packages/*".to_string()])
+        );
+
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "workspaces": { "packages": ["apps/*"] } }"#,
+        );
+        write(
+            dir.path(),
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'libs/**'\n  - '!libs/internal'\n",
+        );
+        assert_eq!(
+            read_workspace_patterns(dir.path()),
+            Some(vec![
+                "apps/*".to_string(),
+                "libs/**".to_string(),
+                "!libs/internal".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_package_for_with_workspaces() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "package.json",
+            r#"{ "name": "monorepo", "workspaces": ["packages/*"] }"#,
+        );
+        write(root, "packages/a/package.json", r#"{ "name": "@scope/a" }"#);
+        write(root, "packages/b/package.json", r#"{}"#);
+        write(
+            root,
+            "packages/a/fixtures/package.json",
+            r#"{ "name": "fixture" }"#,
+        );
+
+        let config = get_default_config();
+        let workspace = Workspace::new(root.to_str().unwrap(), root.to_str().unwrap(), &config);
+        let package_name = |path: &str| {
+            workspace
+                .package_for(&root.join(path))
+                .map(|package| package.name.clone())
+        };
+
+        assert_eq!(
+            package_name("packages/a/src/index.ts"),
+            Some("@scope/a".to_string())
+        );
+        // Not a declared workspace, so it belongs to the enclosing package
+        assert_eq!(
+            package_name("packages/a/fixtures/x.ts"),
+            Some("@scope/a".to_string())
+        );
+        // Packages without a name are named after their directory
+        assert_eq!(
+            package_name("packages/b/index.ts"),
+            Some("packages/b".to_string())
+        );
+        assert_eq!(
+            package_name("scripts/build.ts"),
+            Some("monorepo".to_string())
+        );
+    }
+
+    #[test]
+    fn test_package_for_with_nested_package_json_boundaries() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "apps/web/package.json", r#"{ "name": "web" }"#);
+        write(
+            root,
+            "apps/web/tools/package.json",
+            r#"{ "name": "web-tools" }"#,
+        );
+
+        let config = get_default_config();
+        let workspace = Workspace::new(root.to_str().unwrap(), root.to_str().unwrap(), &config);
+        let package_name = |path: &str| {
+            workspace
+                .package_for(&root.join(path))
+                .map(|package| package.name.clone())
+        };
+
+        assert_eq!(
+            package_name("apps/web/src/app.tsx"),
+            Some("web".to_string())
+        );
+        assert_eq!(
+            package_name("apps/web/tools/x.ts"),
+            Some("web-tools".to_string())
+        );
+        assert_eq!(package_name("lib/x.ts"), None);
+    }
+
+    #[test]
+    fn test_nested_configs_apply_to_their_subtree() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "packages/a/package.json", r#"{ "name": "a" }"#);
+        write(
+            root,
+            "packages/a/fta.json",
+            r#"{ "score_cap": 70, "extensions": [".mts"] }"#,
+        );
+        write(
+            root,
+            "packages/a/src/generated/fta.yaml",
+            "exclude_under: 1000\noverrides:\n  - files: ['*.spec.ts']\n    score_cap: 100\n",
+        );
+        write(root, "packages/b/package.json", r#"{ "name": "b" }"#);
+
+        let mut config = get_default_config();
+        config.score_cap = 90;
+        let workspace = Workspace::new(root.to_str().unwrap(), root.to_str().unwrap(), &config);
+
+        let a_config = workspace
+            .config_for(&root.join("packages/a/index.ts"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(a_config.score_cap, 70);
+        assert!(a_config.extensions.contains(&".ts".to_string()));
+        assert!(a_config.extensions.contains(&".mts".to_string()));
+
+        let generated_config = workspace
+            .config_for(&root.join("packages/a/src/generated/api/client.ts"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(generated_config.score_cap, 70);
+        assert_eq!(generated_config.exclude_under, 1000);
+        assert!(generated_config.extensions.contains(&".mts".to_string()));
+        assert_eq!(
+            generated_config.overrides[0].files,
+            Some(vec!["packages/a/src/generated/**/*.spec.ts".to_string()])
+        );
+
+        assert!(workspace
+            .config_for(&root.join("packages/b/index.ts"))
+            .unwrap()
+            .is_none());
+        assert!(workspace
+            .config_for(&root.join("index.ts"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_nested_configs_exclude_directories_in_their_subtree() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "packages/foo/fta.json",
+            r#"{ "exclude_directories": ["/generated", "fixtures", "/src/*/", "!/src/keep"] }"#,
+        );
+
+        let config = get_default_config();
+        let workspace = Workspace::new(root.to_str().unwrap(), root.to_str().unwrap(), &config);
+        let foo_config = workspace
+            .config_for(&root.join("packages/foo/generated/x.ts"))
+            .unwrap()
+            .unwrap();
+        let is_valid = |path: &str| is_valid_path("x.ts", path, &foo_config);
+
+        assert!(foo_config
+            .exclude_directories
+            .contains(&"/packages/foo/generated".to_string()));
+        assert!(foo_config
+            .exclude_directories
+            .contains(&"/dist".to_string()));
+        assert!(!is_valid("packages/foo/generated/x.ts"));
+        assert!(!is_valid("packages/foo/test/fixtures/x.ts"));
+        assert!(!is_valid("packages/foo/src/api/x.ts"));
+        assert!(is_valid("packages/foo/src/keep/x.ts"));
+        assert!(is_valid("packages/foo/src/x.ts"));
+        assert!(is_valid("packages/foo/lib/x.ts"));
+    }
+
+    #[test]
+    fn test_nested_configs_cannot_loosen_command_line_settings() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "pkg/fta.json",
+            r#"{ "score_cap": 1000, "exclude_under": 0, "overrides": [{ "files": ["*.spec.ts"], "score_cap": 2000 }] }"#,
+        );
+
+        let mut sources = ConfigSources::default();
+        let mut config = get_default_config();
+        for setting in ["score_cap=50", "exclude_directories+=/gen"] {
+            let provided_config = parse_setting(setting, "--set").unwrap();
+            config = apply_setting(&config, &mut sources, provided_config, "--set").unwrap();
+        }
+        config.exclude_under = 10;
+        config.command_line.exclude_under = Some(10);
+        let workspace = Workspace::new(root.to_str().unwrap(), root.to_str().unwrap(), &config);
+
+        let pkg_config = workspace
+            .config_for(&root.join("pkg/index.ts"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(pkg_config.score_cap, 50);
+        assert_eq!(pkg_config.exclude_under, 10);
+        let gen_directories = pkg_config
+            .exclude_directories
+            .iter()
+            .filter(|directory| *directory == "/gen")
+            .count();
+        assert_eq!(gen_directories, 1);
+
+        let spec_config = apply_overrides(&pkg_config, "pkg/index.spec.ts", &[]).unwrap();
+        assert_eq!(spec_config.score_cap, 50);
+    }
+
+    #[test]
+    fn test_invalid_nested_configs_are_errors() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "pkg/fta.json", r#"{ "score_cap": "high" }"#);
+
+        let config = get_default_config();
+        let workspace = Workspace::new(root.to_str().unwrap(), root.to_str().unwrap(), &config);
+
+        let error = workspace
+            .config_for(&root.join("pkg/src/index.ts"))
+            .unwrap_err();
+        assert!(error.to_string().contains("fta.json"));
+        assert!(workspace
+            .config_for(&root.join("index.ts"))
+            .unwrap()
+            .is_none());
+    }
+}
@@ -16,9 +16,19 @@ declare module "fta-cli" {
    * @property {number} halstead.effort - An estimation of the amount of work required to write a program. E = D * V.
    * @property {number} halstead.time - An estimation of the time required to write the program. T = E / 18 (seconds).
    * @property {number} halstead.bugs - An estimation of the number of bugs in the program. B = V / 3000.
-   * @property {number} line_count - The number of lines in the file.
+   * @property {number} line_count - The number of lines in the file the score counts, see `score_lines`.
+   * @property {Object} lines - How the lines of the file break down.
    * @property {number} fta_score - The FTA score of the file.
    * @property {string} assessment - The assessment of the file.
+   * @property {string} syntax - The syntax the file was parsed with: typescript, tsx, javascript or jsx.
+   * @property {string} source_type - Whether the file was parsed as an ES module or a script, e.g. CommonJS.
+   * @property {string} [package] - The name of the workspace package the file belongs to, if any.
+   * @property {string[]} [owners] - The file's owners according to CODEOWNERS, if any.
+   * @property {Object[]} [suppressions] - The `fta-` suppression comments in the file, if any.
+   * @property {number} [gate_fta_score] - The FTA score without the suppressed lines and functions, if any.
+   * @property {Object} [parse_failure] - Why the file couldn't be parsed, in which case it has no metrics.
+   * @property {string} [skipped] - Why the file wasn't analyzed, e.g. it couldn't be read or looks generated.
+   * @property {string} [analysis_failure] - Why the analysis of the file was cut short, e.g. it took longer than `file_timeout_ms`.
    */
   export type AnalyzedFile = {
     /**
@@ -108,11 +118,54 @@ declare module "fta-cli" {
       bugs: number;
     };
     /**
-     * The number of lines in the file.
+     * The number of lines in the file the score counts: source lines by default, see `score_lines`.
      *
      * @type {number}
      */
     line_count: number;
+    /**
+     * How the lines of the file break down.
+     *
+     * @type {Object}
+     */
+    lines: {
+      /**
+       * Every line of the file.
+       *
+       * @type {number}
+       */
+      physical: number;
+      /**
+       * The lines with code, including those that also have a comment.
+       *
+       * @type {number}
+       */
+      source: number;
+      /**
+       * The lines with comments and no code.
+       *
+       * @type {number}
+       */
+      comment: number;
+      /**
+       * The empty and whitespace-only lines, even inside block comments.
+       *
+       * @type {number}
+       */
+      blank: number;
+      /**
+       * The lines with both code and a comment.
+       *
+       * @type {number}
+       */
+      mixed: number;
+      /**
+       * The statements and declarations.
+       *
+       * @type {number}
+       */
+      logical: number;
+    };
     /**
      * The FTA score of the file.
      *
@@ -125,6 +178,106 @@ declare module "fta-cli" {
      * @type {string}
      */
     assessment: string;
+    /**
+     * The syntax the file was parsed with, picked from its extension.
+     *
+     * @type {string}
+     */
+    syntax: "typescript" | "tsx" | "javascript" | "jsx";
+    /**
+     * Whether the file was parsed as an ES module or a script, e.g. CommonJS.
+     *
+     * @type {string}
+     */
+    source_type: "module" | "script";
+    /**
+     * The name of the workspace package the file belongs to, if any.
+     *
+     * @type {string}
+     */
+    package?: string;
+    /**
+     * The file's owners according to CODEOWNERS, if any.
+     *
+     * @type {string[]}
+     */
+    owners?: string[];
+    /**
+     * The `fta-` suppression comments in the file, if any.
+     *
+     * @type {Object[]}
+     */
+    suppressions?: {
+      /**
+       * What the suppression applies to: `file`, `line` or `function`.
+       *
+       * @type {string}
+       */
+      kind: "file" | "line" | "function";
+      /**
+       * The rules a `fta-disable` comment turns off. Empty when all rules are turned off.
+       *
+       * @type {string[]}
+       */
+      rules?: string[];
+      /**
+       * The line the suppression applies to.
+       *
+       * @type {number}
+       */
+      line: number;
+      /**
+       * The reason given in the comment, if any.
+       *
+       * @type {string}
+       */
+      reason?: string;
+    }[];
+    /**
+     * The FTA score without the suppressed lines and functions, used for the score cap.
+     *
+     * @type {number}
+     */
+    gate_fta_score?: number;
+    /**
+     * Why the file couldn't be parsed, if it couldn't. Its metrics are then all zero.
+     *
+     * @type {Object}
+     */
+    parse_failure?: {
+      /**
+       * The syntax error.
+       *
+       * @type {string}
+       */
+      message: string;
+      /**
+       * The line of the error, starting at 1.
+       *
+       * @type {number}
+       */
+      line: number;
+      /**
+       * The column of the error, starting at 1.
+       *
+       * @type {number}
+       */
+      column: number;
+    };
+    /**
+     * Why the file wasn't analyzed, when it couldn't be read or decoded, is larger than
+     * `max_file_size` or looks generated or minified, in which case it has no metrics.
+     *
+     * @type {string}
+     */
+    skipped?: string;
+    /**
+     * Why the analysis of the file was cut short, when it panicked or took longer than
+     * `file_timeout_ms`, in which case it has no metrics.
+     *
+     * @type {string}
+     */
+    analysis_failure?: string;
   };
 
   /**